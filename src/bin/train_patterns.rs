@@ -0,0 +1,94 @@
+//! Fits pattern evaluation weights to a set of recorded games.
+//!
+//! Usage: `train_patterns <games file> <weights file> [--anti] [--steps N]`
+//!
+//! The games file holds one game per line, as its moves followed by the final score,
+//! such as `f5d6c3d3c4 ... 36-28`. Every tenth game is held back to check that the
+//! weights also predict games they weren't trained on. Training continues from the
+//! weights file if it already exists, as long as it was written for the same ruleset,
+//! and the weights are saved there at the end.
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use othello_rs::engine::{GameRecord, PatternWeights, Trainer, TrainingSettings};
+use othello_rs::game_logic::Ruleset;
+
+const USAGE: &str = "Usage: train_patterns <games file> <weights file> [--anti] [--steps N]";
+
+struct Options {
+    games: String,
+    weights: String,
+    ruleset: Ruleset,
+    steps: u32
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match train(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, &'static str> {
+    let mut paths = Vec::new();
+    let mut ruleset = Ruleset::Standard;
+    let mut steps = 300;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--anti" => ruleset = Ruleset::AntiOthello,
+            "--steps" => {
+                steps = args.next()
+                    .and_then(|steps| steps.parse().ok())
+                    .ok_or("The number of steps must be a whole number.")?;
+            },
+            _ => paths.push(arg)
+        }
+    }
+
+    let [games, weights] = paths.try_into().map_err(|_| "Expected a games file and a weights file.")?;
+    Ok(Options { games, weights, ruleset, steps })
+}
+
+fn train(options: &Options) -> Result<(), &'static str> {
+    let games = GameRecord::load_all(&options.games)?;
+    let mut weights = match Path::new(&options.weights).exists() {
+        true => PatternWeights::load(&options.weights, options.ruleset)?,
+        false => PatternWeights::new(options.ruleset)
+    };
+
+    let mut training = Trainer::new(options.ruleset);
+    let mut validation = Trainer::new(options.ruleset);
+    for (index, game) in games.iter().enumerate() {
+        match index % 10 {
+            9 => validation.add_game(game)?,
+            _ => training.add_game(game)?
+        }
+    }
+    println!("Training on {} positions, validating on {}.", training.len(), validation.len());
+
+    let settings = TrainingSettings::default();
+    for step in 1..=options.steps {
+        let error = training.step(&mut weights, &settings);
+        if step % 10 == 0 || step == options.steps {
+            let validation_error = validation.error(&weights);
+            println!("step {step}: training error {error:.2}, validation error {validation_error:.2}");
+        }
+    }
+
+    weights.save(&options.weights)?;
+    println!("Saved the weights to {}.", options.weights);
+    Ok(())
+}
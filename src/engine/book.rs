@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::game_logic::{Board, OthelloBoard, Ruleset, Square, Symmetry};
+use super::decision::{Decision, Engine};
+use super::evaluation;
+use super::search::Ply;
+
+/// How many moves into a game its positions are added to the book when learning.
+pub const BOOK_DEPTH: usize = 16;
+
+/// How a move out of a book position has fared so far.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BookMove {
+    pub square: Square,
+    /// The average final margin of the player making the move, as worked out by `final_margin`.
+    pub score: f32,
+    /// The number of games the score is based on.
+    pub games: u32
+}
+
+/// Positions from the start of games along with how the moves played from them scored.
+/// Positions are stored in their canonical form, so a known position is found in the
+/// book however it is rotated or reflected. Scores only make sense for one ruleset, so
+/// each ruleset needs a book of its own.
+#[derive(Clone, Debug, Default)]
+pub struct OpeningBook {
+    // book moves in the frame of the canonical position, keyed by its notation
+    positions: HashMap<String, Vec<BookMove>>
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        OpeningBook { positions: HashMap::new() }
+    }
+
+    /// The Standard Othello book bundled in `assets/opening_book.txt`.
+    pub fn standard() -> Self {
+        include_str!("../../assets/opening_book.txt").parse()
+            .expect("The bundled opening book is valid.")
+    }
+
+    /// Reads a book from a text file, in the format described for `from_str`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        fs::read_to_string(path)
+            .map_err(|_| "Could not read the opening book file.")?
+            .parse()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), &'static str> {
+        fs::write(path, self.to_string()).map_err(|_| "Could not write the opening book file.")
+    }
+
+    /// The number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the book moves for the side to move on this board, best first, or nothing
+    /// when the position is out of book. Moves that lead to the same position by symmetry
+    /// share their score.
+    pub fn lookup(&self, board: &OthelloBoard) -> Vec<BookMove> {
+        let (canonical, symmetry) = board.canonical();
+        let Some(book_moves) = self.positions.get(&canonical.to_string()) else {
+            return Vec::new();
+        };
+
+        let mut moves: Vec<BookMove> = Vec::new();
+        for book_move in book_moves {
+            for equivalent in equivalent_squares(&canonical, book_move.square) {
+                let square = symmetry.apply(equivalent, board.size());
+                if !moves.iter().any(|other| other.square == square) {
+                    moves.push(BookMove { square, ..*book_move });
+                }
+            }
+        }
+
+        moves.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.games.cmp(&a.games)));
+        moves
+    }
+
+    /// The best scoring book move for the side to move, if the position is in the book.
+    pub fn best_move(&self, board: &OthelloBoard) -> Option<BookMove> {
+        self.lookup(board).first().copied()
+    }
+
+    /// Adds the result of a finished game to the moves played in its first `BOOK_DEPTH`
+    /// turns, scored by the final margin of whoever played them.
+    pub fn learn(&mut self, game: &OthelloBoard, ruleset: Ruleset) -> Result<(), &'static str> {
+        if !game.is_game_over() {
+            return Err("Only finished games can be added to the opening book.")
+        }
+
+        let mut board = game.clone();
+        while board.undo().is_some() {}
+
+        for played in game.history().iter().take(BOOK_DEPTH) {
+            if board.side_to_move() != played.player {
+                board.pass()?;
+            }
+
+            let score = evaluation::final_margin(game, played.player, ruleset) as f32;
+            self.add_move(&board, BookMove { square: played.square, score, games: 1 });
+            board.set_piece(played.square, played.player)?;
+        }
+        Ok(())
+    }
+
+    /// Merges a move from the given position into the book, averaging its score with
+    /// the games already recorded for it.
+    fn add_move(&mut self, board: &OthelloBoard, book_move: BookMove) {
+        let (canonical, symmetry) = board.canonical();
+        let square = symmetry.inverse().apply(book_move.square, board.size());
+        let square = equivalent_squares(&canonical, square).into_iter()
+            .min_by_key(|square| square.index(board.size()))
+            .expect("Every square is at least equivalent to itself.");
+
+        let book_moves = self.positions.entry(canonical.to_string()).or_default();
+        match book_moves.iter_mut().find(|known| known.square == square) {
+            Some(known) => {
+                let games = known.games + book_move.games;
+                known.score = (known.score * known.games as f32 + book_move.score * book_move.games as f32)
+                    / games as f32;
+                known.games = games;
+            },
+            None => book_moves.push(BookMove { square, ..book_move })
+        }
+    }
+}
+
+/// Squares that lead to the same position as the given one, because the board looks
+/// the same after some rotation or reflection. The square itself is always included.
+fn equivalent_squares(board: &OthelloBoard, square: Square) -> Vec<Square> {
+    let position = board.to_string();
+    let mut squares: Vec<Square> = Vec::new();
+    for symmetry in Symmetry::ALL {
+        let equivalent = symmetry.apply(square, board.size());
+        if !squares.contains(&equivalent) && board.transformed(symmetry).to_string() == position {
+            squares.push(equivalent);
+        }
+    }
+    squares
+}
+
+/// Writes one book move per line, as the position in the notation of `OthelloBoard`,
+/// followed by the move, its average score and the number of games behind it.
+impl fmt::Display for OpeningBook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# position, side to move, move, average final margin, games")?;
+
+        let mut positions: Vec<(&String, &Vec<BookMove>)> = self.positions.iter().collect();
+        positions.sort_by_key(|(position, _)| *position);
+        for (position, book_moves) in positions {
+            for book_move in book_moves {
+                writeln!(f, "{} {} {:.2} {}", position, book_move.square, book_move.score, book_move.games)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a book in the format written by `Display`. Blank lines and lines starting
+/// with `#` are skipped, and every move is checked to be legal in its position.
+impl FromStr for OpeningBook {
+    type Err = &'static str;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut book = OpeningBook::new();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let [squares, side_to_move, square, score, games] = line.split_whitespace()
+                .collect::<Vec<&str>>()
+                .try_into()
+                .map_err(|_| "Invalid book line given. It needs a position, side to move, move, \
+                score and number of games.")?;
+
+            let board: OthelloBoard = format!("{squares} {side_to_move}").parse()?;
+            let square: Square = square.parse()?;
+            if !board.legal_moves(board.side_to_move()).contains(&square) {
+                return Err("Invalid book move given. It is not legal in its position.")
+            }
+
+            let score = score.parse().map_err(|_| "Invalid book score given. It must be a number.")?;
+            let games = games.parse().map_err(|_| "Invalid number of book games given.")?;
+            book.add_move(&board, BookMove { square, score, games });
+        }
+        Ok(book)
+    }
+}
+
+/// Plays the best book move instantly while the game is still in the book, and asks
+/// another engine once it isn't.
+pub struct BookEngine {
+    book: OpeningBook,
+    engine: Box<dyn Engine + Send>
+}
+
+impl BookEngine {
+    pub fn new(book: OpeningBook, engine: Box<dyn Engine + Send>) -> Self {
+        BookEngine { book, engine }
+    }
+}
+
+impl Engine for BookEngine {
+    fn decide(&mut self, board: &OthelloBoard) -> Decision {
+        let Some(book_move) = self.book.best_move(board) else {
+            return self.engine.decide(board);
+        };
+
+        // Average margins of around ten discs already make for a fairly safe win.
+        Decision {
+            best_move: Some(book_move.square),
+            confidence: 1.0 / (1.0 + (-book_move.score / 10.0).exp()),
+            principal_variation: vec![Ply::Move(book_move.square)]
+        }
+    }
+}
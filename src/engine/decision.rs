@@ -0,0 +1,19 @@
+use crate::game_logic::{OthelloBoard, Square};
+use super::search::Ply;
+
+/// The move a computer player settled on, whichever engine found it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decision {
+    /// The move to play, or `None` when the side to move has to pass or the game is over.
+    pub best_move: Option<Square>,
+    /// How likely the engine thinks the side to move is to win after the best move,
+    /// from 0 for a certain loss to 1 for a certain win.
+    pub confidence: f32,
+    /// The line both sides are expected to play, starting with the best move.
+    pub principal_variation: Vec<Ply>
+}
+
+/// Anything that can pick a move for the side to move.
+pub trait Engine {
+    fn decide(&mut self, board: &OthelloBoard) -> Decision;
+}
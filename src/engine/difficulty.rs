@@ -0,0 +1,111 @@
+use crate::game_logic::{BitBoard, Board, OthelloBoard, Rng, Ruleset};
+use super::book::{BookEngine, OpeningBook};
+use super::decision::{Decision, Engine};
+use super::endgame::{EndgameSolver, SolveMode};
+use super::evaluation::{Evaluator, HeuristicEvaluator};
+use super::mcts::{MonteCarlo, MonteCarloSettings, PlayoutPolicy};
+use super::patterns::{PatternEvaluator, PatternWeights};
+use super::search::{AlphaBeta, SearchLimits};
+
+/// How strong a computer opponent plays.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    /// A quick Monte Carlo search with purely random playouts, which blunders often.
+    Easy,
+    /// A shallow alpha-beta search with the hand-tuned evaluation.
+    #[default]
+    Medium,
+    /// A deeper search that knows the opening book and uses trained pattern weights
+    /// when there are any.
+    Hard,
+    /// Like `Hard` but searching further, and playing the endgame perfectly once few
+    /// enough squares are left to solve it exactly.
+    Perfect
+}
+
+/// Solving endgames with more empty squares than this takes too long to wait for, even
+/// on a `BitBoard`.
+const PERFECT_ENDGAME_EMPTIES: usize = 14;
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Perfect
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Perfect => "Perfect"
+        }
+    }
+
+    /// Builds the engine that plays at this level. The book only applies to Standard
+    /// Othello and the pattern weights only to the ruleset they were trained for, so
+    /// other rulesets go without them.
+    pub fn engine(&self, ruleset: Ruleset, book: &OpeningBook, weights: Option<&PatternWeights>)
+        -> Box<dyn Engine + Send> {
+        let evaluator = strongest_evaluator(ruleset, weights);
+        let with_book = |engine: Box<dyn Engine + Send>| -> Box<dyn Engine + Send> {
+            match ruleset {
+                Ruleset::Standard => Box::new(BookEngine::new(book.clone(), engine)),
+                Ruleset::AntiOthello => engine
+            }
+        };
+
+        match self {
+            Difficulty::Easy => {
+                let settings = MonteCarloSettings {
+                    playouts: 300,
+                    policy: PlayoutPolicy::Random,
+                    ..MonteCarloSettings::default()
+                };
+                Box::new(MonteCarlo::new(settings, ruleset, Rng::from_time()))
+            },
+            Difficulty::Medium => Box::new(AlphaBeta::new(limits(3, None), ruleset)),
+            Difficulty::Hard => {
+                with_book(Box::new(AlphaBeta::with_evaluator(limits(6, Some(300_000)), ruleset, evaluator)))
+            },
+            Difficulty::Perfect => {
+                let search = AlphaBeta::with_evaluator(limits(10, Some(1_500_000)), ruleset, evaluator);
+                with_book(Box::new(SolvingEngine {
+                    search,
+                    solver: EndgameSolver::new(ruleset, SolveMode::Exact)
+                }))
+            }
+        }
+    }
+}
+
+/// The pattern evaluation when there are weights for the ruleset, and the hand-tuned
+/// one otherwise.
+pub fn strongest_evaluator(ruleset: Ruleset, weights: Option<&PatternWeights>) -> Box<dyn Evaluator + Send> {
+    match weights.filter(|weights| weights.ruleset() == ruleset) {
+        Some(weights) => Box::new(PatternEvaluator::new(weights.clone())),
+        None => Box::new(HeuristicEvaluator::new(ruleset))
+    }
+}
+
+fn limits(max_depth: u32, max_nodes: Option<u64>) -> SearchLimits {
+    SearchLimits { max_depth, max_nodes }
+}
+
+/// Searches until the endgame is small enough to solve, and solves it from there on. Only
+/// boards that fit a `BitBoard` are solved: on any other the solver has no budget and is
+/// far too slow, so those are left to the search.
+struct SolvingEngine {
+    search: AlphaBeta,
+    solver: EndgameSolver
+}
+
+impl Engine for SolvingEngine {
+    fn decide(&mut self, board: &OthelloBoard) -> Decision {
+        let solvable = board.count_empty_squares() <= PERFECT_ENDGAME_EMPTIES
+            && BitBoard::try_from(board).is_ok();
+        match solvable {
+            true => self.solver.decide(board),
+            false => self.search.decide(board)
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::cmp::Reverse;
+
+use crate::game_logic::{BitBoard, OthelloBoard, Ruleset, Square};
+use super::decision::{Decision, Engine};
+use super::evaluation;
+use super::search::Ply;
+use super::search_board::SearchBoard;
+
+/// How much an endgame search has to find out.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SolveMode {
+    /// The exact final margin, which takes the longest.
+    Exact,
+    /// Only whether the game is won, lost or drawn, which allows far more cutoffs.
+    WinLossDraw
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss
+}
+
+/// `best_move` and `principal_variation` are as in `Decision`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EndgameResult {
+    pub best_move: Option<Square>,
+    /// The final margin of the side to move under perfect play, or only its sign.
+    pub margin: i32,
+    pub principal_variation: Vec<Ply>,
+    pub nodes: u64
+}
+
+impl EndgameResult {
+    pub fn outcome(&self) -> Outcome {
+        match self.margin.signum() {
+            1 => Outcome::Win,
+            0 => Outcome::Draw,
+            _ => Outcome::Loss
+        }
+    }
+}
+
+/// Finds the result under perfect play, feasible up to about 20 empty squares.
+pub struct EndgameSolver {
+    ruleset: Ruleset,
+    mode: SolveMode,
+    nodes: u64
+}
+
+// Below this many empty squares, ordering moves costs more time than it saves.
+const FASTEST_FIRST_EMPTIES: usize = 6;
+
+impl EndgameSolver {
+    pub fn new(ruleset: Ruleset, mode: SolveMode) -> Self {
+        EndgameSolver { ruleset, mode, nodes: 0 }
+    }
+
+    pub fn solve(&mut self, board: &OthelloBoard) -> EndgameResult {
+        match BitBoard::try_from(board) {
+            Ok(mut bit_board) => self.solve_on(&mut bit_board),
+            Err(_) => self.solve_on(&mut board.clone())
+        }
+    }
+
+    fn solve_on<B: SearchBoard>(&mut self, board: &mut B) -> EndgameResult {
+        self.nodes = 0;
+
+        // No margin can go beyond the number of squares, and a window of one either way
+        // around a draw is enough to tell a win from a draw or a loss.
+        let bound = match self.mode {
+            SolveMode::Exact => (board.size() * board.size()) as i32 + 1,
+            SolveMode::WinLossDraw => 1
+        };
+
+        let mut principal_variation = Vec::new();
+        let margin = self.negamax(board, -bound, bound, &mut principal_variation);
+
+        EndgameResult {
+            best_move: match principal_variation.first() {
+                Some(Ply::Move(square)) => Some(*square),
+                _ => None
+            },
+            margin: match self.mode {
+                SolveMode::Exact => margin,
+                SolveMode::WinLossDraw => margin.signum()
+            },
+            principal_variation,
+            nodes: self.nodes
+        }
+    }
+
+    fn negamax<B: SearchBoard>(&mut self, board: &mut B, mut alpha: i32, beta: i32,
+        principal_variation: &mut Vec<Ply>) -> i32 {
+        self.nodes += 1;
+
+        let player = board.side_to_move();
+        let mut moves = board.legal_moves(player);
+        if moves.is_empty() {
+            if board.is_game_over() {
+                principal_variation.clear();
+                return evaluation::margin_of_score(self.ruleset.final_score(board), player, self.ruleset)
+            }
+
+            let mut passed = board.clone();
+            passed.pass_turn();
+
+            let mut line = Vec::new();
+            let margin = -self.negamax(&mut passed, -beta, -alpha, &mut line);
+            principal_variation.clear();
+            principal_variation.push(Ply::Pass);
+            principal_variation.extend(line);
+            return margin
+        }
+
+        // Trying the moves that leave the opponent the fewest replies first keeps the tree
+        // narrow and tends to find the best move early.
+        if board.count_empty_squares() > FASTEST_FIRST_EMPTIES {
+            moves.sort_by_cached_key(|square| {
+                board.play(*square);
+                let replies = board.count_legal_moves(player.opponent());
+                board.take_back();
+                (replies, Reverse(is_corner(board.size(), *square)))
+            });
+        }
+
+        // Once the first move has set the bar, the others only have to be shown worse with
+        // a zero-width window, and need a full search only when they turn out better.
+        let mut best_margin = i32::MIN;
+        for (index, square) in moves.into_iter().enumerate() {
+            board.play(square);
+            let mut line = Vec::new();
+            let mut margin = match index {
+                0 => -self.negamax(board, -beta, -alpha, &mut line),
+                _ => -self.negamax(board, -alpha - 1, -alpha, &mut line)
+            };
+            if index > 0 && alpha < margin && margin < beta {
+                margin = -self.negamax(board, -beta, -margin, &mut line);
+            }
+            board.take_back();
+
+            if margin > best_margin {
+                best_margin = margin;
+                principal_variation.clear();
+                principal_variation.push(Ply::Move(square));
+                principal_variation.extend(line);
+            }
+
+            alpha = alpha.max(margin);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best_margin
+    }
+}
+
+impl Engine for EndgameSolver {
+    fn decide(&mut self, board: &OthelloBoard) -> Decision {
+        let result = self.solve(board);
+        Decision {
+            best_move: result.best_move,
+            confidence: match result.outcome() {
+                Outcome::Win => 1.0,
+                Outcome::Draw => 0.5,
+                Outcome::Loss => 0.0
+            },
+            principal_variation: result.principal_variation
+        }
+    }
+}
+
+fn is_corner(size: usize, square: Square) -> bool {
+    let last = size - 1;
+    (square.rank() == 0 || square.rank() == last) && (square.file() == 0 || square.file() == last)
+}
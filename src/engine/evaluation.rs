@@ -0,0 +1,172 @@
+use crate::game_logic::analysis;
+use crate::game_logic::{BitBoard, Board, OthelloBoard, Player, Ruleset, Square};
+
+/// The score of a won game before its margin is added, beyond any evaluation.
+pub const WIN_SCORE: i32 = 1_000_000;
+
+/// Scores positions from the given player's point of view.
+pub trait Evaluator {
+    fn evaluate(&self, board: &OthelloBoard, player: Player) -> i32;
+
+    /// Scores a `BitBoard` exactly as `evaluate` scores the same position.
+    fn evaluate_bit_board(&self, board: &BitBoard, player: Player) -> i32;
+}
+
+/// A hand-tuned evaluation that works on every board size and layout.
+#[derive(Clone, Debug, Default)]
+pub struct HeuristicEvaluator {
+    ruleset: Ruleset
+}
+
+/// What the heuristic weighs, each as the player's count minus their opponent's.
+struct Features {
+    mobility: i32,
+    potential_mobility: i32,
+    frontier_discs: i32,
+    discs: i32,
+    corners: i32,
+    x_squares: i32,
+    empty_squares: usize
+}
+
+impl HeuristicEvaluator {
+    pub fn new(ruleset: Ruleset) -> Self {
+        HeuristicEvaluator { ruleset }
+    }
+
+    fn score(&self, features: Features) -> i32 {
+        // Pieces only start to matter once the board fills up, while mobility matters throughout.
+        let disc_weight = match features.empty_squares {
+            0..=12 => 40,
+            13..=24 => 10,
+            _ => 0
+        };
+        let material = 800 * features.corners - 250 * features.x_squares + disc_weight * features.discs;
+
+        let positional = 60 * features.mobility + 20 * features.potential_mobility
+            - 25 * features.frontier_discs;
+        match self.ruleset {
+            Ruleset::Standard => positional + material,
+            Ruleset::AntiOthello => positional - material
+        }
+    }
+}
+
+impl Evaluator for HeuristicEvaluator {
+    fn evaluate(&self, board: &OthelloBoard, player: Player) -> i32 {
+        let opponent = player.opponent();
+        let (own_pieces, opponent_pieces) = own_and_opponent(board.count_pieces(), player);
+        let piece_at = |square| board.get_piece_at(square);
+
+        self.score(Features {
+            mobility: difference(analysis::mobility(board, player), analysis::mobility(board, opponent)),
+            potential_mobility: difference(
+                analysis::potential_mobility(board, player),
+                analysis::potential_mobility(board, opponent)
+            ),
+            frontier_discs: difference(
+                analysis::frontier_discs(board, player).len(),
+                analysis::frontier_discs(board, opponent).len()
+            ),
+            discs: difference(own_pieces, opponent_pieces),
+            corners: corners(board.size(), piece_at, player),
+            x_squares: x_squares(board.size(), piece_at, |square| board.is_blocked(square), player),
+            empty_squares: board.count_empty_squares()
+        })
+    }
+
+    fn evaluate_bit_board(&self, board: &BitBoard, player: Player) -> i32 {
+        let opponent = player.opponent();
+        let (own_pieces, opponent_pieces) = own_and_opponent(board.count_pieces(), player);
+        let piece_at = |square| board.get_piece_at(square);
+
+        self.score(Features {
+            mobility: difference(board.count_legal_moves(player), board.count_legal_moves(opponent)),
+            potential_mobility: difference(
+                board.potential_mobility(player),
+                board.potential_mobility(opponent)
+            ),
+            frontier_discs: difference(
+                board.count_frontier_discs(player),
+                board.count_frontier_discs(opponent)
+            ),
+            discs: difference(own_pieces, opponent_pieces),
+            corners: corners(board.size(), piece_at, player),
+            x_squares: x_squares(board.size(), piece_at, |_| false, player),
+            empty_squares: board.count_empty_squares()
+        })
+    }
+}
+
+/// The player's winning margin in a finished game, where positive is always a win.
+pub fn final_margin(board: &OthelloBoard, player: Player, ruleset: Ruleset) -> i32 {
+    margin_of_score(ruleset.final_score(board), player, ruleset)
+}
+
+/// The player's winning margin given black's and white's final score.
+pub(super) fn margin_of_score(score: (usize, usize), player: Player, ruleset: Ruleset) -> i32 {
+    let (own_score, opponent_score) = own_and_opponent(score, player);
+    match ruleset {
+        Ruleset::Standard => difference(own_score, opponent_score),
+        Ruleset::AntiOthello => difference(opponent_score, own_score)
+    }
+}
+
+/// The score of a finished game for the player, beyond any evaluation.
+pub fn terminal_score(board: &OthelloBoard, player: Player, ruleset: Ruleset) -> i32 {
+    score_of_margin(final_margin(board, player, ruleset))
+}
+
+/// The search score of a finished game won by the given margin, as `terminal_score` gives it.
+pub(super) fn score_of_margin(margin: i32) -> i32 {
+    margin + WIN_SCORE * margin.signum()
+}
+
+fn difference(own: usize, opponent: usize) -> i32 {
+    own as i32 - opponent as i32
+}
+
+/// Turns a pair of black's and white's counts into the player's and their opponent's.
+fn own_and_opponent((black, white): (usize, usize), player: Player) -> (usize, usize) {
+    match player {
+        Player::Black => (black, white),
+        Player::White => (white, black)
+    }
+}
+
+/// Each corner of the board along with its diagonal neighbour, the X-square.
+fn corner_squares(size: usize) -> [(Square, Square); 4] {
+    let last = size - 1;
+    let square = |rank: usize, file: usize| {
+        Square::new(rank, file).expect("Squares near the corners lie on every board.")
+    };
+    [
+        (square(0, 0), square(1, 1)),
+        (square(0, last), square(1, last - 1)),
+        (square(last, 0), square(last - 1, 1)),
+        (square(last, last), square(last - 1, last - 1)),
+    ]
+}
+
+fn corners(size: usize, piece_at: impl Fn(Square) -> Option<Player>, player: Player) -> i32 {
+    corner_squares(size).iter()
+        .map(|(corner, _)| match piece_at(*corner) {
+            Some(owner) if owner == player => 1,
+            Some(_) => -1,
+            None => 0
+        })
+        .sum()
+}
+
+// X-squares are only a weakness while their corner is still free to be taken.
+fn x_squares(size: usize, piece_at: impl Fn(Square) -> Option<Player>, is_blocked: impl Fn(Square) -> bool,
+    player: Player) -> i32 {
+    corner_squares(size).iter()
+        .filter(|(corner, _)| piece_at(*corner).is_none() && !is_blocked(*corner))
+        .map(|(_, x_square)| match piece_at(*x_square) {
+            Some(owner) if owner == player => 1,
+            Some(_) => -1,
+            None => 0
+        })
+        .sum()
+}
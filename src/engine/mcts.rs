@@ -0,0 +1,251 @@
+use std::f64::consts::SQRT_2;
+
+use crate::game_logic::{BitBoard, OthelloBoard, Player, Rng, Ruleset, Square};
+use super::decision::{Decision, Engine};
+use super::search::{self, Ply};
+use super::search_board::SearchBoard;
+
+/// How a playout picks its moves.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PlayoutPolicy {
+    /// Any legal move, all equally likely. The fastest, but the least informed.
+    Random,
+    /// A random move among the best kind available: corners first, X-squares last.
+    #[default]
+    Heuristic
+}
+
+/// How much work a Monte Carlo search does and how it goes about it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MonteCarloSettings {
+    /// The number of games played out to the end, each of which adds one position to the tree.
+    pub playouts: u32,
+    /// How strongly UCT favours rarely tried moves over the ones that have done well so far.
+    pub exploration: f64,
+    pub policy: PlayoutPolicy
+}
+
+impl Default for MonteCarloSettings {
+    fn default() -> Self {
+        MonteCarloSettings {
+            playouts: 5000,
+            exploration: SQRT_2,
+            policy: PlayoutPolicy::default()
+        }
+    }
+}
+
+/// `best_move` and `principal_variation` are as in `Decision`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonteCarloResult {
+    pub best_move: Option<Square>,
+    /// The share of playouts through the best move that were won, draws counting half.
+    pub win_rate: f32,
+    pub principal_variation: Vec<Ply>,
+    pub playouts: u32
+}
+
+/// A Monte Carlo tree search using UCT. The same seed always leads to the same moves.
+pub struct MonteCarlo {
+    settings: MonteCarloSettings,
+    ruleset: Ruleset,
+    rng: Rng,
+    nodes: Vec<Node>
+}
+
+struct Node {
+    ply: Option<Ply>,
+    /// The player who made the ply leading here, whose wins this node counts.
+    mover: Player,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Ply>,
+    visits: u32,
+    wins: f64
+}
+
+impl Node {
+    fn new<B: SearchBoard>(ply: Option<Ply>, mover: Player, parent: Option<usize>, board: &B) -> Self {
+        Node { ply, mover, parent, children: Vec::new(), untried: plies(board), visits: 0, wins: 0.0 }
+    }
+
+    fn win_rate(&self) -> f64 {
+        match self.visits {
+            0 => 0.5,
+            visits => self.wins / visits as f64
+        }
+    }
+}
+
+impl MonteCarlo {
+    pub fn new(settings: MonteCarloSettings, ruleset: Ruleset, rng: Rng) -> Self {
+        MonteCarlo { settings, ruleset, rng, nodes: Vec::new() }
+    }
+
+    /// Searches for the best move for the side to move, which is the one tried the most.
+    pub fn search(&mut self, board: &OthelloBoard) -> MonteCarloResult {
+        match BitBoard::try_from(board) {
+            Ok(bit_board) => self.search_on(&bit_board),
+            Err(_) => self.search_on(board)
+        }
+    }
+
+    fn search_on<B: SearchBoard>(&mut self, board: &B) -> MonteCarloResult {
+        self.nodes.clear();
+        self.nodes.push(Node::new(None, board.side_to_move().opponent(), None, board));
+
+        let playouts = self.settings.playouts.max(1);
+        for _ in 0..playouts {
+            let mut board = board.clone();
+            let leaf = self.select_and_expand(&mut board);
+            let winner = self.play_out(&mut board);
+            self.back_propagate(leaf, winner);
+        }
+
+        let mut principal_variation = Vec::new();
+        let mut node = 0;
+        while let Some(child) = self.most_tried_child(node) {
+            principal_variation.extend(self.nodes[child].ply);
+            node = child;
+        }
+
+        // The root counts the wins of the player who moved last, so in a finished game
+        // the side to move won the rest.
+        let win_rate = match self.most_tried_child(0) {
+            Some(child) => self.nodes[child].win_rate(),
+            None => 1.0 - self.nodes[0].win_rate()
+        };
+
+        MonteCarloResult {
+            best_move: match principal_variation.first() {
+                Some(Ply::Move(square)) => Some(*square),
+                _ => None
+            },
+            win_rate: win_rate as f32,
+            principal_variation,
+            playouts
+        }
+    }
+
+    /// Walks down the most promising moves and adds the first untried one to the tree.
+    fn select_and_expand<B: SearchBoard>(&mut self, board: &mut B) -> usize {
+        let mut node = 0;
+        while self.nodes[node].untried.is_empty() && !self.nodes[node].children.is_empty() {
+            node = self.select_child(node);
+            play(board, self.nodes[node].ply.expect("Only the root has no ply."));
+        }
+
+        let untried = &mut self.nodes[node].untried;
+        if untried.is_empty() {
+            return node
+        }
+
+        let ply = untried.swap_remove(self.rng.below(untried.len()));
+        let mover = board.side_to_move();
+        play(board, ply);
+
+        let child = self.nodes.len();
+        self.nodes.push(Node::new(Some(ply), mover, Some(node), board));
+        self.nodes[node].children.push(child);
+        child
+    }
+
+    fn select_child(&self, node: usize) -> usize {
+        let parent_visits = (self.nodes[node].visits as f64).ln();
+        let upper_confidence_bound = |child: &usize| {
+            let child = &self.nodes[*child];
+            child.win_rate() + self.settings.exploration * (parent_visits / child.visits as f64).sqrt()
+        };
+
+        *self.nodes[node].children.iter()
+            .max_by(|a, b| upper_confidence_bound(a).total_cmp(&upper_confidence_bound(b)))
+            .expect("Only nodes with children are selected from.")
+    }
+
+    fn most_tried_child(&self, node: usize) -> Option<usize> {
+        self.nodes[node].children.iter().copied().max_by_key(|child| self.nodes[*child].visits)
+    }
+
+    /// Finishes the game with quick moves and returns its winner, or `None` for a draw.
+    fn play_out<B: SearchBoard>(&mut self, board: &mut B) -> Option<Player> {
+        loop {
+            let moves = board.legal_moves(board.side_to_move());
+            if moves.is_empty() {
+                if board.is_game_over() {
+                    break;
+                }
+                board.pass_turn();
+                continue;
+            }
+
+            let square = match self.settings.policy {
+                PlayoutPolicy::Random => moves[self.rng.below(moves.len())],
+                PlayoutPolicy::Heuristic => self.heuristic_move(board.size(), &moves)
+            };
+            board.play(square);
+        }
+
+        self.ruleset.winner(self.ruleset.final_score(board))
+    }
+
+    fn heuristic_move(&mut self, size: usize, moves: &[Square]) -> Square {
+        let priority = |square: &Square| {
+            let priority = search::move_priority(size, *square, None);
+            match self.ruleset {
+                Ruleset::Standard => priority,
+                Ruleset::AntiOthello => -priority
+            }
+        };
+
+        let best_priority = moves.iter().map(priority).max().expect("There is at least one move.");
+        let best_moves: Vec<Square> = moves.iter().copied()
+            .filter(|square| priority(square) == best_priority)
+            .collect();
+        best_moves[self.rng.below(best_moves.len())]
+    }
+
+    fn back_propagate(&mut self, leaf: usize, winner: Option<Player>) {
+        let mut next = Some(leaf);
+        while let Some(node) = next {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            node.wins += match winner {
+                Some(player) if player == node.mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5
+            };
+            next = node.parent;
+        }
+    }
+}
+
+impl Engine for MonteCarlo {
+    fn decide(&mut self, board: &OthelloBoard) -> Decision {
+        let result = self.search(board);
+        Decision {
+            best_move: result.best_move,
+            confidence: result.win_rate,
+            principal_variation: result.principal_variation
+        }
+    }
+}
+
+/// Every ply the side to move can make, which is a lone pass when they have no moves.
+fn plies<B: SearchBoard>(board: &B) -> Vec<Ply> {
+    let moves = board.legal_moves(board.side_to_move());
+    if !moves.is_empty() {
+        return moves.into_iter().map(Ply::Move).collect()
+    }
+
+    match board.is_game_over() {
+        true => Vec::new(),
+        false => vec![Ply::Pass]
+    }
+}
+
+fn play<B: SearchBoard>(board: &mut B, ply: Ply) {
+    match ply {
+        Ply::Move(square) => board.play(square),
+        Ply::Pass => board.pass_turn()
+    }
+}
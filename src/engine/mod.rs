@@ -0,0 +1,20 @@
+mod evaluation;
+mod search;
+mod endgame;
+mod search_board;
+mod mcts;
+mod decision;
+mod book;
+mod patterns;
+mod training;
+mod difficulty;
+
+pub use evaluation::{Evaluator, HeuristicEvaluator, WIN_SCORE, final_margin, terminal_score};
+pub use search::{AlphaBeta, Ply, SearchLimits, SearchResult};
+pub use endgame::{EndgameResult, EndgameSolver, Outcome, SolveMode};
+pub use mcts::{MonteCarlo, MonteCarloResult, MonteCarloSettings, PlayoutPolicy};
+pub use decision::{Decision, Engine};
+pub use book::{BOOK_DEPTH, BookEngine, BookMove, OpeningBook};
+pub use patterns::{PatternEvaluator, PatternWeights, STAGES};
+pub use training::{GameRecord, Trainer, TrainingSettings};
+pub use difficulty::{Difficulty, strongest_evaluator};
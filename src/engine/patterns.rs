@@ -0,0 +1,346 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::game_logic::{BitBoard, OthelloBoard, Player, Ruleset, Square, Symmetry};
+use super::evaluation::{Evaluator, HeuristicEvaluator};
+
+/// The number of game stages with weights of their own, by how full the board is.
+pub const STAGES: usize = 6;
+
+const BOARD_SIZE: usize = 8;
+
+/// A group of squares whose contents are scored together, as one entry in a table
+/// holding a weight for each way of filling them. Its instances are found through all
+/// eight symmetries of the board and share the same table, so that the table learns
+/// from every corner and edge at once.
+struct Pattern {
+    name: &'static str,
+    // rank and file of each square of the first instance
+    squares: &'static [(usize, usize)]
+}
+
+/// The usual patterns: an edge along with its two X-squares, the corner regions, the
+/// second to fourth lines and every diagonal of four squares or more.
+const PATTERNS: [Pattern; 11] = [
+    Pattern {
+        name: "edge2x",
+        squares: &[(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5), (0, 6), (0, 7), (1, 1), (1, 6)]
+    },
+    Pattern {
+        name: "corner3x3",
+        squares: &[(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+    },
+    Pattern {
+        name: "corner2x5",
+        squares: &[(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (1, 0), (1, 1), (1, 2), (1, 3), (1, 4)]
+    },
+    Pattern {
+        name: "line2",
+        squares: &[(1, 0), (1, 1), (1, 2), (1, 3), (1, 4), (1, 5), (1, 6), (1, 7)]
+    },
+    Pattern {
+        name: "line3",
+        squares: &[(2, 0), (2, 1), (2, 2), (2, 3), (2, 4), (2, 5), (2, 6), (2, 7)]
+    },
+    Pattern {
+        name: "line4",
+        squares: &[(3, 0), (3, 1), (3, 2), (3, 3), (3, 4), (3, 5), (3, 6), (3, 7)]
+    },
+    Pattern {
+        name: "diagonal8",
+        squares: &[(0, 0), (1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6), (7, 7)]
+    },
+    Pattern {
+        name: "diagonal7",
+        squares: &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 7)]
+    },
+    Pattern {
+        name: "diagonal6",
+        squares: &[(0, 2), (1, 3), (2, 4), (3, 5), (4, 6), (5, 7)]
+    },
+    Pattern {
+        name: "diagonal5",
+        squares: &[(0, 3), (1, 4), (2, 5), (3, 6), (4, 7)]
+    },
+    Pattern {
+        name: "diagonal4",
+        squares: &[(0, 4), (1, 5), (2, 6), (3, 7)]
+    },
+];
+
+/// Where each pattern's table starts within the weights of a stage, and the squares
+/// of every instance of the patterns on the board.
+struct Layout {
+    offsets: Vec<usize>,
+    stage_size: usize,
+    instances: Vec<(usize, Vec<Square>)>
+}
+
+fn layout() -> &'static Layout {
+    static LAYOUT: OnceLock<Layout> = OnceLock::new();
+    LAYOUT.get_or_init(|| {
+        // The first weight of every stage is its bias, scored for every position.
+        let mut offsets = Vec::new();
+        let mut stage_size = 1;
+        let mut instances = Vec::new();
+
+        for (index, pattern) in PATTERNS.iter().enumerate() {
+            offsets.push(stage_size);
+            stage_size += 3_usize.pow(pattern.squares.len() as u32);
+
+            // Symmetric patterns such as the edges and diagonals map onto their own squares
+            // in reverse, and each set of squares is only scored once, in the order first found.
+            let mut square_sets: Vec<Vec<usize>> = Vec::new();
+            for symmetry in Symmetry::ALL {
+                let squares: Vec<Square> = pattern.squares.iter()
+                    .map(|(rank, file)| Square::new(*rank, *file).expect("Pattern squares lie on the board."))
+                    .map(|square| symmetry.apply(square, BOARD_SIZE))
+                    .collect();
+
+                let mut square_set: Vec<usize> = squares.iter().map(|square| square.index(BOARD_SIZE)).collect();
+                square_set.sort_unstable();
+                if !square_sets.contains(&square_set) {
+                    square_sets.push(square_set);
+                    instances.push((index, squares));
+                }
+            }
+        }
+
+        Layout { offsets, stage_size, instances }
+    })
+}
+
+/// Whether patterns can score the board, which has to be a plain 8x8 board past the opening.
+fn fits_patterns(board: &OthelloBoard) -> bool {
+    board.size() == BOARD_SIZE
+        && !board.in_opening_phase()
+        && !board.squares().any(|square| board.is_blocked(square))
+}
+
+fn stage((black, white): (usize, usize)) -> usize {
+    ((black + white).saturating_sub(4) / 10).min(STAGES - 1)
+}
+
+/// The weights that apply to the board from the player's point of view: the stage's bias
+/// and one entry of each pattern instance's table. Each square of an instance counts as
+/// a base-3 digit, 0 for empty, 1 for the player's own piece and 2 for the opponent's.
+pub(super) fn features(board: &OthelloBoard, player: Player) -> Option<Vec<usize>> {
+    if !fits_patterns(board) {
+        return None
+    }
+    Some(features_of(stage(board.count_pieces()), |square| board.get_piece_at(square), player))
+}
+
+fn features_of(stage: usize, piece_at: impl Fn(Square) -> Option<Player>, player: Player) -> Vec<usize> {
+    let layout = layout();
+    let stage_offset = stage * layout.stage_size;
+    let mut features = vec![stage_offset];
+
+    for (pattern, squares) in &layout.instances {
+        let index = squares.iter().rev().fold(0, |index, square| {
+            let digit = match piece_at(*square) {
+                None => 0,
+                Some(owner) if owner == player => 1,
+                Some(_) => 2
+            };
+            index * 3 + digit
+        });
+        features.push(stage_offset + layout.offsets[*pattern] + index);
+    }
+    features
+}
+
+/// How many weights add up to the score of a position, one for each pattern instance
+/// and one for the bias.
+pub(super) fn features_per_position() -> usize {
+    layout().instances.len() + 1
+}
+
+/// The weights of every pattern table for every stage, which add up to a prediction of
+/// the final margin. Weights for one ruleset are no good for the other, so they keep
+/// the ruleset they are for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternWeights {
+    ruleset: Ruleset,
+    weights: Vec<f32>
+}
+
+impl PatternWeights {
+    /// Weights that are all zero, as a starting point for training for the ruleset.
+    pub fn new(ruleset: Ruleset) -> Self {
+        PatternWeights { ruleset, weights: vec![0.0; STAGES * layout().stage_size] }
+    }
+
+    /// Reads weights for the given ruleset from a text file, in the format described
+    /// for `from_str`. Weights written for the other ruleset are rejected.
+    pub fn load(path: impl AsRef<Path>, ruleset: Ruleset) -> Result<Self, &'static str> {
+        let weights: PatternWeights = fs::read_to_string(path)
+            .map_err(|_| "Could not read the pattern weights file.")?
+            .parse()?;
+
+        if weights.ruleset != ruleset {
+            return Err("The pattern weights file was trained for another ruleset.")
+        }
+        Ok(weights)
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        self.ruleset
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), &'static str> {
+        fs::write(path, self.to_string()).map_err(|_| "Could not write the pattern weights file.")
+    }
+
+    /// The final margin predicted for the player, or `None` for boards that patterns
+    /// don't cover, which are those that aren't plain 8x8 boards past the opening.
+    pub fn predict(&self, board: &OthelloBoard, player: Player) -> Option<f32> {
+        let features = features(board, player)?;
+        Some(features.iter().map(|feature| self.weights[*feature]).sum())
+    }
+
+    /// The final margin predicted for the player on a `BitBoard`, which patterns always cover.
+    pub fn predict_bit_board(&self, board: &BitBoard, player: Player) -> f32 {
+        features_of(stage(board.count_pieces()), |square| board.get_piece_at(square), player).iter()
+            .map(|feature| self.weights[*feature])
+            .sum()
+    }
+
+    pub(super) fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    pub(super) fn weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+}
+
+/// Writes the ruleset the weights are for on a line of its own, such as `ruleset standard`,
+/// followed by one weight per line, leaving out the ones that are zero. Each of those lines
+/// holds the pattern's name, the stage, the contents of its squares as base-3 digits, and
+/// the weight. Stage biases are written as the pattern `bias` with `-` for its squares.
+impl fmt::Display for PatternWeights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ruleset {}", ruleset_keyword(self.ruleset))?;
+        writeln!(f, "# pattern, stage, squares (0 empty, 1 own, 2 opponent's), weight")?;
+
+        let layout = layout();
+        for stage in 0..STAGES {
+            let stage_offset = stage * layout.stage_size;
+            if self.weights[stage_offset] != 0.0 {
+                writeln!(f, "bias {} - {}", stage, self.weights[stage_offset])?;
+            }
+
+            for (pattern, offset) in PATTERNS.iter().zip(&layout.offsets) {
+                for index in 0..3_usize.pow(pattern.squares.len() as u32) {
+                    let weight = self.weights[stage_offset + offset + index];
+                    if weight == 0.0 {
+                        continue;
+                    }
+
+                    let digits: String = (0..pattern.squares.len())
+                        .map(|n| char::from(b'0' + (index / 3_usize.pow(n as u32) % 3) as u8))
+                        .collect();
+                    writeln!(f, "{} {} {} {}", pattern.name, stage, digits, weight)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses weights in the format written by `Display`, where the ruleset has to come
+/// before any weight. Blank lines and lines starting with `#` are skipped, and any weight
+/// not listed is zero.
+impl FromStr for PatternWeights {
+    type Err = &'static str;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let layout = layout();
+        let mut lines = text.lines().map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        let ruleset = match lines.next().and_then(|line| line.strip_prefix("ruleset ")) {
+            Some("standard") => Ruleset::Standard,
+            Some("anti-othello") => Ruleset::AntiOthello,
+            Some(_) => return Err("Unknown ruleset given for the pattern weights."),
+            None => return Err("The pattern weights must start with the ruleset they are for.")
+        };
+        let mut weights = PatternWeights::new(ruleset);
+
+        for line in lines {
+            let [name, stage, digits, weight] = line.split_whitespace()
+                .collect::<Vec<&str>>()
+                .try_into()
+                .map_err(|_| "Invalid weight line given. It needs a pattern, stage, squares and weight.")?;
+
+            let stage: usize = stage.parse().ok()
+                .filter(|stage| *stage < STAGES)
+                .ok_or("Invalid stage given for a pattern weight.")?;
+            let weight: f32 = weight.parse()
+                .map_err(|_| "Invalid pattern weight given. It must be a number.")?;
+
+            let offset = match name {
+                "bias" => 0,
+                _ => {
+                    let pattern = PATTERNS.iter().position(|pattern| pattern.name == name)
+                        .ok_or("Unknown pattern name given.")?;
+                    layout.offsets[pattern] + parse_digits(digits, PATTERNS[pattern].squares.len())?
+                }
+            };
+            weights.weights[stage * layout.stage_size + offset] = weight;
+        }
+        Ok(weights)
+    }
+}
+
+fn ruleset_keyword(ruleset: Ruleset) -> &'static str {
+    match ruleset {
+        Ruleset::Standard => "standard",
+        Ruleset::AntiOthello => "anti-othello"
+    }
+}
+
+/// Reads the base-3 digits of a pattern's squares, the first square being the lowest digit.
+fn parse_digits(digits: &str, length: usize) -> Result<usize, &'static str> {
+    if digits.len() != length {
+        return Err("Invalid pattern squares given. There must be one digit for each square.")
+    }
+
+    digits.chars().rev().try_fold(0, |index, digit| match digit.to_digit(3) {
+        Some(digit) => Ok(index * 3 + digit as usize),
+        None => Err("Invalid pattern squares given. Each square must be 0, 1 or 2.")
+    })
+}
+
+/// Scores positions with trained pattern weights, in hundredths of a disc of predicted
+/// final margin, for the ruleset the weights are for. Boards that patterns don't cover,
+/// such as other sizes or boards with blocked squares, are left to a `HeuristicEvaluator`.
+#[derive(Clone, Debug)]
+pub struct PatternEvaluator {
+    weights: PatternWeights,
+    fallback: HeuristicEvaluator
+}
+
+impl PatternEvaluator {
+    pub fn new(weights: PatternWeights) -> Self {
+        let fallback = HeuristicEvaluator::new(weights.ruleset());
+        PatternEvaluator { weights, fallback }
+    }
+}
+
+impl Evaluator for PatternEvaluator {
+    fn evaluate(&self, board: &OthelloBoard, player: Player) -> i32 {
+        match self.weights.predict(board, player) {
+            Some(margin) => (margin * 100.0).round() as i32,
+            None => self.fallback.evaluate(board, player)
+        }
+    }
+
+    fn evaluate_bit_board(&self, board: &BitBoard, player: Player) -> i32 {
+        (self.weights.predict_bit_board(board, player) * 100.0).round() as i32
+    }
+}
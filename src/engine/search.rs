@@ -0,0 +1,247 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::game_logic::{BitBoard, OthelloBoard, Ruleset, Square};
+use super::decision::{Decision, Engine};
+use super::evaluation::{self, Evaluator, HeuristicEvaluator, WIN_SCORE};
+use super::search_board::SearchBoard;
+
+// beyond any score a search can return, so that it can serve as the initial bounds
+const INFINITY: i32 = WIN_SCORE * 2;
+
+/// One turn in a line of play, which is either a move or a forced pass.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Ply {
+    Move(Square),
+    Pass
+}
+
+impl fmt::Display for Ply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ply::Move(square) => write!(f, "{}", square),
+            Ply::Pass => write!(f, "pass")
+        }
+    }
+}
+
+/// How far a search may go. It deepens one move at a time until either limit is reached.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub max_depth: u32,
+    pub max_nodes: Option<u64>
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        SearchLimits {
+            max_depth: 6,
+            max_nodes: None
+        }
+    }
+}
+
+/// `best_move` and `principal_variation` are as in `Decision`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub best_move: Option<Square>,
+    /// The score for the side to move. Finished games score beyond `WIN_SCORE`.
+    pub score: i32,
+    pub principal_variation: Vec<Ply>,
+    /// The deepest search that was completed.
+    pub depth: u32,
+    pub nodes: u64
+}
+
+/// A negamax search with alpha-beta pruning and iterative deepening.
+pub struct AlphaBeta {
+    limits: SearchLimits,
+    ruleset: Ruleset,
+    evaluator: Box<dyn Evaluator + Send>,
+    best_moves: HashMap<u64, Square>,
+    nodes: u64,
+    completed_depth: u32,
+    aborted: bool
+}
+
+impl AlphaBeta {
+    pub fn new(limits: SearchLimits, ruleset: Ruleset) -> Self {
+        Self::with_evaluator(limits, ruleset, Box::new(HeuristicEvaluator::new(ruleset)))
+    }
+
+    pub fn with_evaluator(limits: SearchLimits, ruleset: Ruleset, evaluator: Box<dyn Evaluator + Send>)
+        -> Self {
+        AlphaBeta {
+            limits,
+            ruleset,
+            evaluator,
+            best_moves: HashMap::new(),
+            nodes: 0,
+            completed_depth: 0,
+            aborted: false
+        }
+    }
+
+    /// Searches for the best move for the side to move. Running out of nodes returns the
+    /// deepest completed search, and the first one always completes.
+    pub fn search(&mut self, board: &OthelloBoard) -> SearchResult {
+        match BitBoard::try_from(board) {
+            Ok(mut bit_board) => self.search_on(&mut bit_board),
+            Err(_) => self.search_on(&mut board.clone())
+        }
+    }
+
+    fn search_on<B: SearchBoard>(&mut self, board: &mut B) -> SearchResult {
+        self.best_moves.clear();
+        self.nodes = 0;
+        self.completed_depth = 0;
+        self.aborted = false;
+
+        let mut result = SearchResult {
+            best_move: None,
+            score: 0,
+            principal_variation: Vec::new(),
+            depth: 0,
+            nodes: 0
+        };
+
+        for depth in 1..=self.limits.max_depth.max(1) {
+            let mut principal_variation = Vec::new();
+            let score = self.negamax(board, depth, -INFINITY, INFINITY, &mut principal_variation);
+            if self.aborted {
+                break;
+            }
+
+            result = SearchResult {
+                best_move: match principal_variation.first() {
+                    Some(Ply::Move(square)) => Some(*square),
+                    _ => None
+                },
+                score,
+                principal_variation,
+                depth,
+                nodes: self.nodes
+            };
+            self.completed_depth = depth;
+
+            // Passes don't count towards the depth, so by now every line has reached the end.
+            if depth as usize >= board.count_empty_squares() {
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+        result
+    }
+
+    fn negamax<B: SearchBoard>(&mut self, board: &mut B, depth: u32, mut alpha: i32, beta: i32,
+        principal_variation: &mut Vec<Ply>) -> i32 {
+        self.nodes += 1;
+        if self.is_out_of_nodes() {
+            self.aborted = true;
+            return 0
+        }
+
+        let player = board.side_to_move();
+        let mut moves = board.legal_moves(player);
+        if moves.is_empty() && board.is_game_over() {
+            let margin = evaluation::margin_of_score(self.ruleset.final_score(board), player, self.ruleset);
+            return evaluation::score_of_margin(margin)
+        }
+
+        if depth == 0 {
+            return board.evaluate(self.evaluator.as_ref(), player)
+        }
+
+        if moves.is_empty() {
+            let mut passed = board.clone();
+            passed.pass_turn();
+
+            let mut line = Vec::new();
+            let score = -self.negamax(&mut passed, depth, -beta, -alpha, &mut line);
+            principal_variation.clear();
+            principal_variation.push(Ply::Pass);
+            principal_variation.extend(line);
+            return score
+        }
+
+        let hash_move = self.best_moves.get(&board.position_key()).copied();
+        moves.sort_by_key(|square| Reverse(move_priority(board.size(), *square, hash_move)));
+
+        let mut best_score = -INFINITY;
+        for square in moves {
+            board.play(square);
+            let mut line = Vec::new();
+            let score = -self.negamax(board, depth - 1, -beta, -alpha, &mut line);
+            board.take_back();
+
+            if self.aborted {
+                return 0
+            }
+
+            if score > best_score {
+                best_score = score;
+                principal_variation.clear();
+                principal_variation.push(Ply::Move(square));
+                principal_variation.extend(line);
+                self.best_moves.insert(board.position_key(), square);
+            }
+
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best_score
+    }
+
+    // The first search always runs to completion, so there is a move to fall back on.
+    fn is_out_of_nodes(&self) -> bool {
+        match self.limits.max_nodes {
+            Some(max_nodes) => self.nodes > max_nodes && self.completed_depth > 0,
+            None => false
+        }
+    }
+}
+
+impl Engine for AlphaBeta {
+    fn decide(&mut self, board: &OthelloBoard) -> Decision {
+        let result = self.search(board);
+        Decision {
+            best_move: result.best_move,
+            confidence: confidence_of_score(result.score),
+            principal_variation: result.principal_variation
+        }
+    }
+}
+
+/// Turns a search score into a chance of winning.
+fn confidence_of_score(score: i32) -> f32 {
+    if score.abs() > WIN_SCORE / 2 {
+        return match score > 0 {
+            true => 1.0,
+            false => 0.0
+        }
+    }
+    1.0 / (1.0 + (-score as f32 / 580.0).exp())
+}
+
+/// How early a move should be tried: the last best move first, then corners, X-squares last.
+pub(super) fn move_priority(size: usize, square: Square, hash_move: Option<Square>) -> i32 {
+    if hash_move == Some(square) {
+        return i32::MAX
+    }
+
+    let last = size - 1;
+    let rank_distance = square.rank().min(last - square.rank());
+    let file_distance = square.file().min(last - square.file());
+
+    match (rank_distance.min(file_distance), rank_distance.max(file_distance)) {
+        (0, 0) => 100,
+        (1, 1) => -100,
+        (0, 1) => -50,
+        (0, _) => 20,
+        _ => 0
+    }
+}
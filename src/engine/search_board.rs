@@ -0,0 +1,55 @@
+use crate::game_logic::{BitBoard, Board, OthelloBoard, Player, Square};
+use super::evaluation::Evaluator;
+
+/// What the searches need on top of `Board`, so plain 8x8 games can run on a `BitBoard`.
+pub(super) trait SearchBoard: Board {
+    fn count_legal_moves(&self, player: Player) -> usize {
+        self.legal_moves(player).len()
+    }
+
+    /// Tells positions apart for move ordering. Two positions may share a key.
+    fn position_key(&self) -> u64;
+    fn evaluate(&self, evaluator: &dyn Evaluator, player: Player) -> i32;
+
+    fn play(&mut self, square: Square) {
+        self.set_piece(square, self.side_to_move()).expect("Legal moves can always be played.");
+    }
+
+    fn take_back(&mut self);
+
+    fn pass_turn(&mut self) {
+        self.pass().expect("A player without moves in an unfinished game must pass.");
+    }
+}
+
+impl SearchBoard for OthelloBoard {
+    fn position_key(&self) -> u64 {
+        self.zobrist_hash()
+    }
+
+    fn evaluate(&self, evaluator: &dyn Evaluator, player: Player) -> i32 {
+        evaluator.evaluate(self, player)
+    }
+
+    fn take_back(&mut self) {
+        self.undo();
+    }
+}
+
+impl SearchBoard for BitBoard {
+    fn count_legal_moves(&self, player: Player) -> usize {
+        self.count_legal_moves(player)
+    }
+
+    fn position_key(&self) -> u64 {
+        self.position_key()
+    }
+
+    fn evaluate(&self, evaluator: &dyn Evaluator, player: Player) -> i32 {
+        evaluator.evaluate_bit_board(self, player)
+    }
+
+    fn take_back(&mut self) {
+        self.undo_quietly();
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::game_logic::{self, Board, OthelloBoard, Ruleset, Square};
+use super::evaluation;
+use super::patterns::{self, PatternWeights};
+
+/// A game played from the standard starting position, kept as its moves and its final
+/// score so that evaluations can be trained on it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub moves: Vec<Square>,
+    /// Black's and white's final score.
+    pub score: (usize, usize)
+}
+
+impl GameRecord {
+    /// Records a finished game, which must have started from the standard position.
+    pub fn from_game(board: &OthelloBoard, ruleset: Ruleset) -> Result<Self, &'static str> {
+        if !board.is_game_over() {
+            return Err("Only finished games can be recorded.")
+        }
+
+        let record = GameRecord {
+            moves: board.history().iter().map(|played| played.square).collect(),
+            score: ruleset.final_score(board)
+        };
+        record.replay()?;
+        Ok(record)
+    }
+
+    /// Reads one game per line from a text file, in the format described for `from_str`.
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn load_all(path: impl AsRef<Path>) -> Result<Vec<Self>, &'static str> {
+        fs::read_to_string(path)
+            .map_err(|_| "Could not read the games file.")?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Plays the moves out from the standard position, calling back with every position
+    /// before a move is made in it. Passes are made whenever they are due.
+    fn replay_with(&self, mut visit: impl FnMut(&OthelloBoard)) -> Result<(), &'static str> {
+        let mut board = OthelloBoard::new();
+        for square in &self.moves {
+            if board.must_pass(board.side_to_move()) {
+                board.pass()?;
+            }
+
+            visit(&board);
+            board.set_piece(*square, board.side_to_move())
+                .map_err(|_| "Invalid game record given. One of its moves is illegal.")?;
+        }
+        Ok(())
+    }
+
+    fn replay(&self) -> Result<(), &'static str> {
+        self.replay_with(|_| {})
+    }
+}
+
+/// Writes the moves run together followed by black's and white's score, such as
+/// `f5d6c3d3c4 ... 36-28`.
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for square in &self.moves {
+            write!(f, "{square}")?;
+        }
+        write!(f, " {}-{}", self.score.0, self.score.1)
+    }
+}
+
+/// Parses a game in the format written by `Display`, and checks that its moves are legal.
+impl FromStr for GameRecord {
+    type Err = &'static str;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (moves, score) = line.trim().rsplit_once(char::is_whitespace)
+            .ok_or("Invalid game record given. It needs the moves followed by the final score.")?;
+
+        let score = score.split_once('-')
+            .and_then(|(black, white)| Some((black.parse().ok()?, white.parse().ok()?)))
+            .ok_or("Invalid final score given. It must be written as black's and white's score, \
+            such as 36-28.")?;
+
+        let record = GameRecord { moves: game_logic::parse_moves(moves)?, score };
+        record.replay()?;
+        Ok(record)
+    }
+}
+
+/// How weights are fitted to the recorded games.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TrainingSettings {
+    /// How far each step moves the weights towards reducing the error. At 1, a position
+    /// whose weights all appear only in it would be predicted exactly after one step,
+    /// so values well below that are needed for training to settle.
+    pub learning_rate: f32,
+    /// How many positions' worth of evidence pull a weight back towards zero, which keeps
+    /// rarely seen patterns from getting extreme weights.
+    pub regularisation: f32
+}
+
+impl Default for TrainingSettings {
+    fn default() -> Self {
+        TrainingSettings {
+            learning_rate: 0.5,
+            regularisation: 50.0
+        }
+    }
+}
+
+/// Fits pattern weights by least squares regression, so that they predict the final
+/// margin of every position in a set of recorded games from the side to move's point
+/// of view. Training runs one step of gradient descent over all the positions at a time.
+pub struct Trainer {
+    ruleset: Ruleset,
+    positions: Vec<TrainingPosition>,
+    // how many positions each weight appears in
+    occurrences: Vec<u32>
+}
+
+struct TrainingPosition {
+    features: Vec<usize>,
+    margin: f32
+}
+
+impl Trainer {
+    pub fn new(ruleset: Ruleset) -> Self {
+        Trainer {
+            ruleset,
+            positions: Vec::new(),
+            occurrences: vec![0; PatternWeights::new(ruleset).weights().len()]
+        }
+    }
+
+    /// Adds every position of the game in which a move was made.
+    pub fn add_game(&mut self, record: &GameRecord) -> Result<(), &'static str> {
+        let mut positions = Vec::new();
+        record.replay_with(|board| {
+            let player = board.side_to_move();
+            if let Some(features) = patterns::features(board, player) {
+                let margin = evaluation::margin_of_score(record.score, player, self.ruleset) as f32;
+                positions.push(TrainingPosition { features, margin });
+            }
+        })?;
+
+        for position in &positions {
+            for feature in &position.features {
+                self.occurrences[*feature] += 1;
+            }
+        }
+        self.positions.extend(positions);
+        Ok(())
+    }
+
+    /// The number of positions trained on.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// The root mean square difference between the predicted and the actual final margins.
+    pub fn error(&self, weights: &PatternWeights) -> f32 {
+        let weights = weights.weights();
+        let squared_error: f32 = self.positions.iter()
+            .map(|position| (position.margin - predict(weights, &position.features)).powi(2))
+            .sum();
+        (squared_error / self.positions.len().max(1) as f32).sqrt()
+    }
+
+    /// Moves every weight against the average error of the positions it appears in,
+    /// and returns the error from before the step.
+    pub fn step(&self, weights: &mut PatternWeights, settings: &TrainingSettings) -> f32 {
+        let weights = weights.weights_mut();
+        let mut gradient = vec![0.0_f32; weights.len()];
+        let mut squared_error = 0.0;
+
+        for position in &self.positions {
+            let error = position.margin - predict(weights, &position.features);
+            squared_error += error * error;
+            for feature in &position.features {
+                gradient[*feature] += error;
+            }
+        }
+
+        // Every weight of a position moves, so each only takes its share of the correction.
+        let share = settings.learning_rate / patterns::features_per_position() as f32;
+        for (feature, weight) in weights.iter_mut().enumerate() {
+            let occurrences = self.occurrences[feature] as f32;
+            if occurrences > 0.0 {
+                let regularised = gradient[feature] - settings.regularisation * *weight;
+                *weight += share * regularised / (occurrences + settings.regularisation);
+            }
+        }
+        (squared_error / self.positions.len().max(1) as f32).sqrt()
+    }
+}
+
+fn predict(weights: &[f32], features: &[usize]) -> f32 {
+    features.iter().map(|feature| weights[*feature]).sum()
+}
@@ -1,6 +1,26 @@
-use crate::game_logic::OthelloBoard;
+use crate::engine::{
+    self, AlphaBeta, BookMove, Decision, Difficulty, Engine, OpeningBook, PatternWeights, SearchLimits,
+    SearchResult, WIN_SCORE
+};
+use crate::game_logic::{Board, Handicap, OpeningList, OthelloBoard, Player, Ruleset, Square};
 use crate::networking::RpcClient;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Where the opening book is kept between sessions in the data directory, as it learns
+/// from finished games.
+const BOOK_FILE: &str = "opening_book.txt";
+/// Trained Standard Othello weights for the stronger computer levels in the data directory,
+/// made with `train_patterns`. Without them those levels use the hand-tuned evaluation.
+const PATTERN_WEIGHTS_FILE: &str = "pattern_weights.txt";
+/// Openings to start games from in the data directory, in place of the bundled list.
+const OPENINGS_FILE: &str = "openings.txt";
+/// Hints come from a short search, so that they show up soon after being asked for.
+const HINT_LIMITS: SearchLimits = SearchLimits { max_depth: 8, max_nodes: Some(100_000) };
 
 #[derive(Copy, Clone)]
 pub enum GameResult {
@@ -9,39 +29,185 @@ pub enum GameResult {
     Tie
 }
 
+/// Options agreed on before a game starts. The host picks them in the main menu
+/// and sends them to the opponent when connecting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameSetup {
+    pub board_size: usize,
+    pub ruleset: Ruleset,
+    /// Classic Reversi opening, where players place the first four pieces themselves.
+    pub free_opening: bool,
+    /// Squares that can never hold a piece, fixed by the host's chosen layout.
+    pub blocked_squares: Vec<Square>,
+    /// Corner pieces the weaker player starts with, if any.
+    pub handicap: Option<Handicap>,
+    /// Moves played out before the game starts, such as a random XOT opening.
+    /// They are part of the starting position and can't be undone.
+    pub opening: Vec<Square>,
+    /// Whether players may ask the engine for a hint. Hints are announced to the
+    /// opponent either way.
+    pub allow_hints: bool
+}
+
+impl Default for GameSetup {
+    fn default() -> Self {
+        GameSetup {
+            board_size: 8,
+            ruleset: Ruleset::Standard,
+            free_opening: false,
+            blocked_squares: Vec::new(),
+            handicap: None,
+            opening: Vec::new(),
+            allow_hints: true
+        }
+    }
+}
+
+impl GameSetup {
+    /// Whether this is a plain game of Standard Othello on an 8x8 board from the usual
+    /// starting position, which is the only kind the opening book can learn from.
+    pub fn is_standard_game(&self) -> bool {
+        *self == GameSetup { allow_hints: self.allow_hints, ..GameSetup::default() }
+    }
+
+    pub fn build_board(&self) -> Result<OthelloBoard, &'static str> {
+        let mut board = match self.free_opening {
+            true => OthelloBoard::empty(self.board_size)?,
+            false => OthelloBoard::with_size(self.board_size)?
+        };
+
+        board.block_squares(&self.blocked_squares)?;
+        if let Some(handicap) = self.handicap {
+            board.place_handicap(handicap)?;
+        }
+
+        for square in &self.opening {
+            board.set_piece(*square, board.side_to_move())?;
+        }
+        Ok(board)
+    }
+}
+
+/// Where the controller keeps the files it reads and writes between sessions:
+/// `$XDG_DATA_HOME/othello-rs`, `~/.local/share/othello-rs` or `%APPDATA%\othello-rs`,
+/// whichever can be found first, and the current directory otherwise.
+pub fn default_data_dir() -> PathBuf {
+    let base = env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from));
+
+    match base {
+        Some(base) => base.join("othello-rs"),
+        None => PathBuf::new()
+    }
+}
+
+struct PendingHint {
+    result: Receiver<SearchResult>,
+    // the position searched, so that a hint for a position since left is dropped
+    position: (u64, usize)
+}
+
+/// A move suggested to the player by a short search.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub square: Square,
+    /// How good the move looks for the player, in discs. It is the exact final margin when
+    /// the search saw the game through to the end, and otherwise the evaluation divided
+    /// by 100, which predicts the margin with pattern weights loaded but is only a rough
+    /// measure of the advantage with the hand-tuned evaluation.
+    pub score: f32,
+    // the position the hint is for, so that it disappears once the board changes
+    position: (u64, usize)
+}
+
 pub enum GameState {
     NoConnection,
     Playing,
     GameEnded(GameResult),
 }
 
+/// A computer playing the other side. Its engine searches on a thread of its own, so
+/// the GUI keeps drawing while it thinks, and is handed back along with its decision.
+struct ComputerOpponent {
+    color: Player,
+    difficulty: Difficulty,
+    // None while the engine is away searching
+    engine: Option<Box<dyn Engine + Send>>,
+    thinking: Option<PendingMove>
+}
+
+struct PendingMove {
+    decision: Receiver<(Box<dyn Engine + Send>, Decision)>,
+    // the position searched, so that a decision for a position since undone is dropped
+    position: (u64, usize)
+}
+
 pub struct GameController {
     pub state: GameState,
     pub board: OthelloBoard,
+    pub setup: GameSetup,
     pub is_host: bool,
     pub player_turn: bool,
-    pub opponent_passed: bool,
     pub error_queue: Arc<Mutex<Vec<String>>>,
+    /// The Standard Othello book, starting from the bundled one until games are added.
+    pub book: OpeningBook,
+    /// The openings random starts are drawn from, the bundled ones unless replaced.
+    pub openings: OpeningList,
+    pattern_weights: Option<PatternWeights>,
+    data_dir: PathBuf,
     chat_messages: Vec<String>,
     rpc_client: Option<RpcClient>,
+    computer: Option<ComputerOpponent>,
+    hint: Option<Hint>,
+    finding_hint: Option<PendingHint>,
+    // the book's moves count as a hint, so they are only shown once asked for
+    showing_book_moves: bool,
+}
+
+impl Default for GameController {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameController {
     pub fn new() -> Self {
+        Self::with_data_dir(default_data_dir())
+    }
+
+    /// Like `new`, but keeps the opening book and looks for openings and pattern weights
+    /// in the given directory instead of the default one.
+    pub fn with_data_dir(data_dir: impl Into<PathBuf>) -> Self {
+        let data_dir = data_dir.into();
+        let pattern_weights_file = data_dir.join(PATTERN_WEIGHTS_FILE);
+
         GameController {
             state: GameState::NoConnection,
             board: OthelloBoard::new(),
+            setup: GameSetup::default(),
             is_host: true,
             player_turn: true,
-            opponent_passed: false,
             chat_messages: Vec::new(),
             rpc_client: None,
-            error_queue: Arc::new(Mutex::new(Vec::new()))
+            computer: None,
+            hint: None,
+            finding_hint: None,
+            showing_book_moves: false,
+            error_queue: Arc::new(Mutex::new(Vec::new())),
+            book: OpeningBook::load(data_dir.join(BOOK_FILE)).unwrap_or_else(|_| OpeningBook::standard()),
+            openings: OpeningList::load(data_dir.join(OPENINGS_FILE))
+                .unwrap_or_else(|_| OpeningList::standard()),
+            pattern_weights: match pattern_weights_file.exists() {
+                true => PatternWeights::load(pattern_weights_file, Ruleset::Standard).ok(),
+                false => None
+            },
+            data_dir
         }
     }
 
-    pub fn get_piece_at(&self, rank: usize, file: usize) -> Option<u8>{
-        self.board.get_piece_at(rank, file)
+    pub fn get_piece_at(&self, square: Square) -> Option<Player> {
+        self.board.get_piece_at(square)
     }
 
     pub fn get_chat_messages(&self) -> &Vec<String> {
@@ -49,62 +215,298 @@ impl GameController {
     }
 
     pub fn connect_to(&mut self, ip_addr: &str) {
-        let mut client = RpcClient::new(ip_addr, self.error_queue.clone()).unwrap();
+        let mut client = match RpcClient::new(ip_addr, self.error_queue.clone()) {
+            Ok(client) => client,
+            Err(error) => {
+                self.error_queue.lock().unwrap().push(format!("Could not connect to {}: {}", ip_addr, error));
+                return;
+            }
+        };
 
         if self.is_host {
-            client.connect_to();
+            if let Err(error) = self.apply_setup(self.setup.clone()) {
+                self.error_queue.lock().unwrap().push(error.to_string());
+                return;
+            }
+            client.connect_to(&self.setup);
         }
 
         self.rpc_client = Some(client);
         self.state = GameState::Playing;
+        self.player_turn = self.board.side_to_move() == self.player_color();
+    }
+
+    /// Starts a game against the computer, which plays the given colour at the given level.
+    pub fn play_against_computer(&mut self, setup: GameSetup, color: Player, difficulty: Difficulty)
+        -> Result<(), &'static str> {
+        self.apply_setup(setup)?;
+        let engine = difficulty.engine(self.setup.ruleset, &self.book, self.pattern_weights.as_ref());
+
+        self.is_host = true;
+        self.rpc_client = None;
+        self.computer = Some(ComputerOpponent { color, difficulty, engine: Some(engine), thinking: None });
+        self.chat_messages = Vec::new();
+        self.state = GameState::Playing;
+        self.player_turn = self.board.side_to_move() == self.player_color();
+        Ok(())
+    }
+
+    pub fn is_against_computer(&self) -> bool {
+        self.computer.is_some()
+    }
+
+    /// Lets the computer take its turn, to be called every frame. The first call on its
+    /// turn starts a search in the background, and a later one plays the move it found,
+    /// starting on the next one straight away if the player has to pass. Returns whether
+    /// the computer is still thinking.
+    pub fn poll_computer(&mut self) -> bool {
+        let Some(computer) = self.computer.as_mut() else {
+            return false
+        };
+
+        if let Some(pending) = &computer.thinking {
+            match pending.decision.try_recv() {
+                Ok((engine, decision)) => {
+                    let searched = pending.position;
+                    computer.engine = Some(engine);
+                    computer.thinking = None;
+                    if searched == self.position() && matches!(self.state, GameState::Playing) && !self.player_turn {
+                        let played = decision.best_move
+                            .ok_or("The computer found no move to play.")
+                            .and_then(|square| self.try_set_piece_on_board(square, true));
+                        if let Err(error) = played {
+                            self.push_error_to_chat(error);
+                            return false
+                        }
+                    }
+                },
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => {
+                    // The engine went down with the thread, so a fresh one takes over.
+                    let difficulty = computer.difficulty;
+                    computer.thinking = None;
+                    computer.engine = Some(difficulty.engine(self.setup.ruleset, &self.book,
+                        self.pattern_weights.as_ref()));
+                    self.push_error_to_chat("The computer stopped thinking without finding a move.");
+                }
+            }
+        }
+
+        let position = self.position();
+        let Some(computer) = self.computer.as_mut() else {
+            return false
+        };
+        if !matches!(self.state, GameState::Playing) || self.player_turn {
+            return false
+        }
+        let Some(mut engine) = computer.engine.take() else {
+            return false
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let board = self.board.clone();
+        thread::spawn(move || {
+            let decision = engine.decide(&board);
+            // Nobody is waiting any more if the game was left in the meantime.
+            let _ = sender.send((engine, decision));
+        });
+        computer.thinking = Some(PendingMove { decision: receiver, position });
+        true
+    }
+
+    /// Starts looking for a move to suggest to the player, in the background like the
+    /// computer's moves. `poll_hint` picks up the hint once it's found.
+    pub fn ask_for_hint(&mut self) -> Result<(), &'static str> {
+        if !self.setup.allow_hints {
+            return Err("Hints are turned off for this game.")
+        }
+        if !matches!(self.state, GameState::Playing) || !self.player_turn {
+            return Err("Hints are only given on your turn.")
+        }
+        if self.is_finding_hint() {
+            return Err("A hint is already on its way.")
+        }
+
+        let ruleset = self.setup.ruleset;
+        let evaluator = engine::strongest_evaluator(ruleset, self.pattern_weights.as_ref());
+        let mut search = AlphaBeta::with_evaluator(HINT_LIMITS, ruleset, evaluator);
+
+        let (sender, receiver) = mpsc::channel();
+        let board = self.board.clone();
+        thread::spawn(move || {
+            // Nobody is waiting any more if the game was left in the meantime.
+            let _ = sender.send(search.search(&board));
+        });
+        self.finding_hint = Some(PendingHint { result: receiver, position: self.position() });
+        Ok(())
+    }
+
+    /// Checks on the hint being looked for, and returns whether it is still on its way.
+    /// A hint found for the current position is shown and announced to the opponent, so
+    /// that hints can't be taken in secret, while one for a position since left is dropped.
+    pub fn poll_hint(&mut self) -> bool {
+        let Some(pending) = &self.finding_hint else {
+            return false
+        };
+
+        let result = match pending.result.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return true,
+            Err(TryRecvError::Disconnected) => {
+                self.finding_hint = None;
+                self.push_error_to_chat("The hint search stopped without finding a move.");
+                return false
+            }
+        };
+
+        let searched = pending.position;
+        self.finding_hint = None;
+        if searched != self.position() || !matches!(self.state, GameState::Playing) {
+            return false
+        }
+
+        let Some(square) = result.best_move else {
+            self.push_error_to_chat("There is no move to suggest.");
+            return false
+        };
+        let score = match result.score.abs() > WIN_SCORE / 2 {
+            true => (result.score - result.score.signum() * WIN_SCORE) as f32,
+            false => result.score as f32 / 100.0
+        };
+        self.hint = Some(Hint { square, score, position: searched });
+
+        if let Some(client) = self.rpc_client.as_mut() {
+            client.announce_hint();
+        }
+        self.push_warning_to_chat(&format!("You took a hint, which suggests {square}."));
+        false
+    }
+
+    pub fn is_finding_hint(&self) -> bool {
+        self.finding_hint.is_some()
     }
 
-    pub fn try_set_piece_on_board(&mut self, rank: usize, file: usize, from_opponent: bool) {
-        if !from_opponent {
-            if !self.player_turn {
-                self.chat_messages.push("ERROR: Wait for your opponent's turn!".to_string());
-                return ()
+    /// The hint last asked for, as long as the board hasn't changed since.
+    pub fn hint(&self) -> Option<&Hint> {
+        self.hint.as_ref().filter(|hint| hint.position == self.position())
+    }
+
+    /// Shows or hides the opening book's moves. They score the moves like a hint does, so
+    /// they can only be shown when hints are allowed, and turning them on is announced to
+    /// the opponent just like taking a hint.
+    pub fn show_book_moves(&mut self, show: bool) -> Result<(), &'static str> {
+        if show && !self.setup.allow_hints {
+            return Err("Hints are turned off for this game, and so is the opening book.")
+        }
+
+        if show && !self.showing_book_moves {
+            if let Some(client) = self.rpc_client.as_mut() {
+                client.announce_hint();
             }
-            self.rpc_client.as_mut().unwrap().set_piece(rank, file);
+            self.push_warning_to_chat("You turned on the opening book, which counts as taking hints.");
+        }
+        self.showing_book_moves = show;
+        Ok(())
+    }
+
+    pub fn is_showing_book_moves(&self) -> bool {
+        self.showing_book_moves
+    }
+
+    /// The book's moves for the side to move, while they are shown. The book only
+    /// knows Standard Othello.
+    pub fn book_moves(&self) -> Vec<BookMove> {
+        if !self.showing_book_moves || self.setup.ruleset != Ruleset::Standard {
+            return Vec::new()
+        }
+        self.book.lookup(&self.board)
+    }
+
+    fn position(&self) -> (u64, usize) {
+        (self.board.zobrist_hash(), self.board.history().len())
+    }
+
+    /// Starts a fresh board following the given setup, which becomes the current one.
+    pub fn apply_setup(&mut self, setup: GameSetup) -> Result<(), &'static str> {
+        // The balanced openings are balanced for an even game, which a handicap is not.
+        if setup.handicap.is_some() && !setup.opening.is_empty() {
+            return Err("A handicap game cannot start from a random opening.")
         }
+        self.board = setup.build_board()?;
+        self.setup = setup;
+        self.showing_book_moves = false;
+        Ok(())
+    }
 
-        let from_opponent = self.swap_player_if_not_host(from_opponent);
-        if let Err(error) = self.board.set_piece(rank, file, from_opponent as u8) {
-            self.chat_messages.push(format!("ERROR: {}", error));
-            return ()
+    pub fn try_set_piece_on_board(&mut self, square: Square, from_opponent: bool)
+        -> Result<(), &'static str> {
+        if !from_opponent && !self.player_turn {
+            return Err("Wait for your opponent's turn!")
         }
 
-        let (p1_pieces, p2_pieces) = self.board.count_pieces();
-        if p1_pieces == 0 || p2_pieces == 0 {
-            self.opponent_passed = true;
-            self.try_pass_turn();
+        if from_opponent && self.player_turn {
+            return Err("It is not the opponent's turn.")
         }
 
-        self.player_turn = !self.player_turn;
-        self.opponent_passed = false;
+        let player = match from_opponent {
+            true => self.player_color().opponent(),
+            false => self.player_color()
+        };
+        self.board.set_piece(square, player)?;
+        self.advance_turn();
+
+        if let (false, Some(client)) = (from_opponent, self.rpc_client.as_mut()) {
+            client.set_piece(square, self.board.zobrist_hash());
+        }
+        Ok(())
     }
 
-    pub fn try_pass_turn(&mut self) {
-        if !self.player_turn {
-            self.chat_messages.push("ERROR: Can't pass if it is not your turn!".to_string());
-            return ();
+    /// Hands the turn over after a move, passing automatically for a player without
+    /// legal moves and ending the game once neither side can move.
+    fn advance_turn(&mut self) {
+        if self.board.is_game_over() {
+            self.learn_from_game();
+            self.state = GameState::GameEnded(self.check_if_player_won());
+            return;
         }
 
-        if self.opponent_passed {
-            let player_won = self.check_if_player_won();
-            self.state = GameState::GameEnded(player_won);
-            self.rpc_client.as_mut().unwrap().end_game(false); //ajeitar dps
+        let next_player = self.board.side_to_move();
+        if self.board.pass().is_ok() {
+            let warning = match (next_player == self.player_color(), self.is_against_computer()) {
+                (true, _) => "You have no legal moves, so your turn was passed.",
+                (false, true) => "The computer has no legal moves, so its turn was passed.",
+                (false, false) => "Your opponent has no legal moves, so their turn was passed."
+            };
+            self.push_warning_to_chat(warning);
         }
 
-        self.player_turn = false;
-        self.opponent_passed = false;
-        self.rpc_client.as_mut().unwrap().change_turn();
+        self.player_turn = self.board.side_to_move() == self.player_color();
+    }
+
+    /// Adds a finished game against the computer to the opening book and saves it, as long
+    /// as it was a standard game. Networked games are left out, as the other side could
+    /// feed the book anything.
+    fn learn_from_game(&mut self) {
+        if !self.is_against_computer() || !self.setup.is_standard_game() {
+            return;
+        }
+
+        let learnt = self.book.learn(&self.board, Ruleset::Standard)
+            .and_then(|_| {
+                fs::create_dir_all(&self.data_dir).map_err(|_| "Could not create the data directory.")
+            })
+            .and_then(|_| self.book.save(self.data_dir.join(BOOK_FILE)));
+        if let Err(error) = learnt {
+            self.push_warning_to_chat(error);
+        }
     }
 
     pub fn push_chat_message(&mut self, msg: String, from_opponent: bool) {
         let msg_with_prefix = match from_opponent {
             false => {
-                self.rpc_client.as_mut().unwrap().send_chat_message(msg.clone());
+                if let Some(client) = self.rpc_client.as_mut() {
+                    client.send_chat_message(msg.clone());
+                }
                 format!("player: {}", msg)
             },
             true => format!("opponent: {}", msg)
@@ -116,48 +518,76 @@ impl GameController {
         self.chat_messages.push(format!("WARNING: {}", msg))
     }
 
+    pub fn push_error_to_chat(&mut self, msg: &str) {
+        self.chat_messages.push(format!("ERROR: {}", msg))
+    }
+
     pub fn surrender(&mut self) {
-        self.rpc_client.as_mut().unwrap().end_game(true);
+        if let Some(client) = self.rpc_client.as_mut() {
+            client.end_game(true);
+        }
         self.state = GameState::GameEnded(GameResult::PlayerLost);
     }
 
+    /// Takes back the last move and tells the opponent. Against the computer, its reply
+    /// is taken back too, so that it's the player's turn again.
     pub fn undo_last_move(&mut self) {
-        self.board.revert_to_last_state();
-        self.player_turn = !self.player_turn;
-        self.opponent_passed = false;
+        if let Err(error) = self.undo_board_move() {
+            self.push_error_to_chat(error);
+            return;
+        }
+
+        if self.is_against_computer() {
+            while !self.player_turn && self.undo_board_move().is_ok() {}
+        }
+        if let Some(client) = self.rpc_client.as_mut() {
+            client.undo_move();
+        }
+    }
+
+    /// Takes back the last move on the board, handing the turn back to whoever played it.
+    pub fn undo_board_move(&mut self) -> Result<(), &'static str> {
+        if !self.setup.opening.is_empty() && self.board.history().len() <= self.setup.opening.len() {
+            return Err("The moves of the starting opening can't be undone.")
+        }
+        self.board.undo().ok_or("There are no moves left to undo.")?;
 
-        self.rpc_client.as_mut().unwrap().undo_move();
+        self.player_turn = self.board.side_to_move() == self.player_color();
+        Ok(())
     }
 
     pub fn restart_game(&mut self) {
         self.state = GameState::NoConnection;
         self.board = OthelloBoard::new();
+        self.is_host = true;
+        self.player_turn = true;
         self.chat_messages = Vec::new();
+        self.rpc_client = None;
+        self.computer = None;
+        self.hint = None;
+        self.finding_hint = None;
+        self.showing_book_moves = false;
     }
 
-    fn swap_player_if_not_host(&self, from_opponent: bool) -> bool {
-        if !self.is_host {
-            !from_opponent
-        } else {
-            from_opponent
+    /// Against the computer the player takes whichever colour it doesn't play. Otherwise
+    /// the host always plays black and moves first.
+    pub fn player_color(&self) -> Player {
+        if let Some(computer) = &self.computer {
+            return computer.color.opponent();
         }
-    }
 
-    pub fn check_if_player_won(&self) -> GameResult {
-        let (p1_pieces, p2_pieces) = self.board.count_pieces();
-
-        if p1_pieces == p2_pieces {
-            return GameResult::Tie
-        }
-
-        if self.is_host && p1_pieces > p2_pieces {
-            return GameResult::PlayerWon
+        match self.is_host {
+            true => Player::Black,
+            false => Player::White
         }
+    }
 
-        if !self.is_host && p2_pieces > p1_pieces {
-            return GameResult::PlayerWon
+    pub fn check_if_player_won(&self) -> GameResult {
+        // Empty squares never change who is ahead, so the piece counts alone decide the game.
+        match self.setup.ruleset.winner(self.board.count_pieces()) {
+            None => GameResult::Tie,
+            Some(winner) if winner == self.player_color() => GameResult::PlayerWon,
+            Some(_) => GameResult::PlayerLost
         }
-
-        GameResult::PlayerLost
     }
 }
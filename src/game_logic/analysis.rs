@@ -0,0 +1,125 @@
+use super::othello_board::DIRECTIONS;
+use super::{Board, Player, Square};
+
+// rank and file steps for the four lines through a square, each also walked backwards
+const AXES: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+/// The positional features of one player's side of the board, as used for teaching
+/// and by evaluation functions, all computed at once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionAnalysis {
+    pub player: Player,
+    pub mobility: usize,
+    pub potential_mobility: usize,
+    pub frontier_discs: Vec<Square>,
+    pub stable_discs: Vec<Square>
+}
+
+impl PositionAnalysis {
+    pub fn new(board: &impl Board, player: Player) -> Self {
+        PositionAnalysis {
+            player,
+            mobility: mobility(board, player),
+            potential_mobility: potential_mobility(board, player),
+            frontier_discs: frontier_discs(board, player),
+            stable_discs: stable_discs(board, player)
+        }
+    }
+}
+
+/// The number of legal moves the player has right now.
+pub fn mobility(board: &impl Board, player: Player) -> usize {
+    board.legal_moves(player).len()
+}
+
+/// The number of free squares next to at least one of the opponent's pieces, which
+/// are the squares the player could get moves on later in the game.
+pub fn potential_mobility(board: &impl Board, player: Player) -> usize {
+    board.squares()
+        .filter(|square| is_free(board, *square))
+        .filter(|square| neighbours(board, *square).any(|neighbour| {
+            board.get_piece_at(neighbour) == Some(player.opponent())
+        }))
+        .count()
+}
+
+/// The player's pieces next to at least one free square. These are the pieces the
+/// opponent can most easily flank, so having few of them is usually good.
+pub fn frontier_discs(board: &impl Board, player: Player) -> Vec<Square> {
+    board.squares()
+        .filter(|square| board.get_piece_at(*square) == Some(player))
+        .filter(|square| neighbours(board, *square).any(|neighbour| is_free(board, neighbour)))
+        .collect()
+}
+
+/// The player's pieces that can never be flipped again for the rest of the game.
+///
+/// A piece is only flipped when it is flanked along one of the four lines through it,
+/// so it is stable once each of those lines is safe: either no square is left free on
+/// it, or one of its two neighbours on it is the edge of the board, a blocked square
+/// or another stable piece of the same player. Starting from the corners, this finds
+/// the pieces anchored to them along the edges and everything anchored to those in turn.
+/// Some stable pieces may be missed, but every piece found is certainly stable.
+pub fn stable_discs(board: &impl Board, player: Player) -> Vec<Square> {
+    let mut stable = vec![false; board.size() * board.size()];
+
+    loop {
+        let mut found_new = false;
+        for square in board.squares() {
+            let index = square.index(board.size());
+            if stable[index] || board.get_piece_at(square) != Some(player) {
+                continue;
+            }
+
+            if AXES.iter().all(|axis| is_axis_safe(board, &stable, square, *axis)) {
+                stable[index] = true;
+                found_new = true;
+            }
+        }
+
+        if !found_new {
+            break;
+        }
+    }
+
+    board.squares().filter(|square| stable[square.index(board.size())]).collect()
+}
+
+fn is_axis_safe(board: &impl Board, stable: &[bool], square: Square, axis: (isize, isize)) -> bool {
+    let (rank_delta, file_delta) = axis;
+    let anchored = [axis, (-rank_delta, -file_delta)].iter().any(|(rank_delta, file_delta)| {
+        match square.offset(*rank_delta, *file_delta, board.size()) {
+            None => true,
+            Some(neighbour) => board.is_blocked(neighbour) || stable[neighbour.index(board.size())]
+        }
+    });
+
+    anchored || is_line_full(board, square, axis)
+}
+
+// Blocked squares cut a line short, since no piece can be flanked across them.
+fn is_line_full(board: &impl Board, square: Square, axis: (isize, isize)) -> bool {
+    let (rank_delta, file_delta) = axis;
+    [axis, (-rank_delta, -file_delta)].iter().all(|(rank_delta, file_delta)| {
+        let mut next = square.offset(*rank_delta, *file_delta, board.size());
+        while let Some(square) = next {
+            if board.is_blocked(square) {
+                break;
+            }
+            if board.get_piece_at(square).is_none() {
+                return false
+            }
+            next = square.offset(*rank_delta, *file_delta, board.size());
+        }
+        true
+    })
+}
+
+fn is_free(board: &impl Board, square: Square) -> bool {
+    board.get_piece_at(square).is_none() && !board.is_blocked(square)
+}
+
+fn neighbours(board: &impl Board, square: Square) -> impl Iterator<Item = Square> + '_ {
+    DIRECTIONS.iter()
+        .filter_map(move |(rank_delta, file_delta)| square.offset(*rank_delta, *file_delta, board.size()))
+}
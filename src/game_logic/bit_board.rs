@@ -0,0 +1,360 @@
+use super::{zobrist, Move, OthelloBoard, Player, Square};
+
+const NOT_FIRST_FILE: u64 = 0xFEFE_FEFE_FEFE_FEFE;
+const NOT_LAST_FILE: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+
+/// Every direction a ray can travel on the board, as a bit shift and the mask that
+/// removes pieces which wrapped around to the opposite edge of the board.
+/// Positive shifts move towards higher ranks/files, negative shifts towards lower ones.
+const DIRECTIONS: [(i32, u64); 8] = [
+    (8, u64::MAX),
+    (-8, u64::MAX),
+    (1, NOT_FIRST_FILE),
+    (-1, NOT_LAST_FILE),
+    (9, NOT_FIRST_FILE),
+    (7, NOT_LAST_FILE),
+    (-7, NOT_FIRST_FILE),
+    (-9, NOT_LAST_FILE),
+];
+
+/// An 8x8 Othello board stored as one `u64` per player, where bit `rank * 8 + file`
+/// is set when the player has a piece on that square. It generates moves and flips with
+/// shifts and masks instead of walking rays square by square, which makes it the board
+/// the engines search on. It implements `Board` just like `OthelloBoard`, so it can stand
+/// in for one wherever a standard 8x8 game is played against that trait, but it has no
+/// sizes, blocked squares, handicaps or free opening. `TryFrom<&OthelloBoard>` copies any
+/// position it can hold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitBoard {
+    pieces: [u64; 2],
+    side_to_move: Player,
+    history: Vec<BitMove>,
+    undone_moves: Vec<BitMove>
+}
+
+/// Compact record of a placement: the player, the placed square and the flipped squares.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct BitMove {
+    player: Player,
+    square: u64,
+    flips: u64
+}
+
+impl From<BitMove> for Move {
+    fn from(bit_move: BitMove) -> Self {
+        Move {
+            player: bit_move.player,
+            square: Square::from_index(bit_move.square.trailing_zeros() as usize, 8),
+            flipped: bits_to_squares(bit_move.flips)
+        }
+    }
+}
+
+/// Copies the position of a standard 8x8 board without blocked squares whose opening is
+/// over, which is every position a `BitBoard` can hold. The move history is left behind.
+impl TryFrom<&OthelloBoard> for BitBoard {
+    type Error = &'static str;
+
+    fn try_from(board: &OthelloBoard) -> Result<Self, Self::Error> {
+        let has_blocked_squares = board.squares().any(|square| board.is_blocked(square));
+        if board.size() != 8 || has_blocked_squares || board.in_opening_phase() {
+            return Err("Only standard 8x8 boards without blocked squares can be turned into a bit board.")
+        }
+
+        let mut pieces = [0; 2];
+        for square in board.squares() {
+            if let Some(player) = board.get_piece_at(square) {
+                pieces[player.index()] |= 1 << square.index(8);
+            }
+        }
+
+        Ok(BitBoard {
+            pieces,
+            side_to_move: board.side_to_move(),
+            history: Vec::new(),
+            undone_moves: Vec::new()
+        })
+    }
+}
+
+impl Default for BitBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitBoard {
+    pub fn new() -> Self {
+        let mut pieces = [0; 2];
+        pieces[Player::Black.index()] = 1 << (4 * 8 + 3) | 1 << (3 * 8 + 4);
+        pieces[Player::White.index()] = 1 << (3 * 8 + 3) | 1 << (4 * 8 + 4);
+
+        BitBoard { pieces, side_to_move: Player::Black, history: Vec::new(), undone_moves: Vec::new() }
+    }
+
+    pub fn size(&self) -> usize {
+        8
+    }
+
+    pub fn side_to_move(&self) -> Player {
+        self.side_to_move
+    }
+
+    /// The same Zobrist hash `OthelloBoard` keeps for the position, worked out from scratch.
+    pub fn zobrist_hash(&self) -> u64 {
+        let pieces = Player::ALL.into_iter().flat_map(|player| {
+            bits_to_squares(self.pieces[player.index()]).into_iter().map(move |square| (square, player))
+        });
+        let hash = pieces.fold(0, |hash, (square, player)| hash ^ zobrist::piece_key(square, player));
+
+        match self.side_to_move {
+            Player::Black => hash,
+            Player::White => hash ^ zobrist::side_to_move_key()
+        }
+    }
+
+    pub fn get_piece_at(&self, square: Square) -> Option<Player> {
+        let bit = square_bit(square)?;
+        Player::ALL.into_iter().find(|player| self.pieces[player.index()] & bit != 0)
+    }
+
+    pub fn set_piece(&mut self, square: Square, player: Player) -> Result<(), &'static str> {
+        let bit = square_bit(square).ok_or("Invalid piece position given. Either the rank or the \
+        file are outside of the game board's upper bounds.")?;
+
+        if self.occupied() & bit != 0 {
+            return Err("There already is a piece at the given position. Pieces must be placed on \
+            empty squares.")
+        }
+
+        if player != self.side_to_move {
+            return Err("It is not this player's turn to move.")
+        }
+
+        let flips = self.flips_mask(bit, player);
+        if flips == 0 {
+            return Err("Invalid move. A piece must outflank at least one of the opponent's pieces \
+            in a straight line.")
+        }
+
+        let new_move = BitMove { player, square: bit, flips };
+        self.apply_move(new_move);
+        self.history.push(new_move);
+        self.undone_moves.clear();
+
+        Ok(())
+    }
+
+    /// Returns the pieces the given player would flip by placing a piece on the square,
+    /// without touching the board, or why that move would be illegal.
+    pub fn flips_for(&self, square: Square, player: Player) -> Result<Vec<Square>, &'static str> {
+        let bit = square_bit(square).ok_or("Invalid piece position given. Either the rank or the \
+        file are outside of the game board's upper bounds.")?;
+
+        if self.occupied() & bit != 0 {
+            return Err("There already is a piece at the given position. Pieces must be placed on \
+            empty squares.")
+        }
+
+        match self.flips_mask(bit, player) {
+            0 => Err("Invalid move. A piece must outflank at least one of the opponent's pieces \
+            in a straight line."),
+            flips => Ok(bits_to_squares(flips))
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<Move> {
+        self.take_back().map(Move::from)
+    }
+
+    /// Takes back the last move like `undo`, but without listing its flipped squares,
+    /// for searches that take back millions of moves.
+    pub(crate) fn undo_quietly(&mut self) -> bool {
+        self.take_back().is_some()
+    }
+
+    pub fn redo(&mut self) -> Option<Move> {
+        let undone_move = self.undone_moves.pop()?;
+        self.apply_move(undone_move);
+        self.history.push(undone_move);
+        Some(undone_move.into())
+    }
+
+    /// How many moves have been played since the board was made, less those taken back.
+    pub fn moves_played(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn legal_moves(&self, player: Player) -> Vec<Square> {
+        bits_to_squares(self.legal_moves_mask(player))
+    }
+
+    /// How many legal moves the player has, without listing them.
+    pub fn count_legal_moves(&self, player: Player) -> usize {
+        self.legal_moves_mask(player).count_ones() as usize
+    }
+
+    pub fn pass(&mut self) -> Result<(), &'static str> {
+        if !self.must_pass(self.side_to_move) {
+            return Err("A player may only pass when they have no legal moves.")
+        }
+
+        self.side_to_move = self.side_to_move.opponent();
+        Ok(())
+    }
+
+    pub fn must_pass(&self, player: Player) -> bool {
+        self.legal_moves_mask(player) == 0 && self.legal_moves_mask(player.opponent()) != 0
+    }
+
+    pub fn count_pieces(&self) -> (usize, usize) {
+        (self.pieces[0].count_ones() as usize, self.pieces[1].count_ones() as usize)
+    }
+
+    /// Counts the move paths of the given length from this position, the same way as
+    /// `OthelloBoard::perft`.
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.clone().count_leaves(depth)
+    }
+
+    fn count_leaves(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1
+        }
+
+        let player = self.side_to_move;
+        let mut moves = self.legal_moves_mask(player);
+        if moves == 0 {
+            if self.legal_moves_mask(player.opponent()) == 0 {
+                return 1
+            }
+
+            self.side_to_move = player.opponent();
+            let leaves = self.count_leaves(depth - 1);
+            self.side_to_move = player;
+            return leaves
+        }
+
+        let mut leaves = 0;
+        while moves != 0 {
+            let bit = moves & moves.wrapping_neg();
+            let new_move = BitMove { player, square: bit, flips: self.flips_mask(bit, player) };
+            self.apply_move(new_move);
+            leaves += self.count_leaves(depth - 1);
+            self.remove_move(new_move);
+            moves &= moves - 1;
+        }
+        leaves
+    }
+
+    /// The number of free squares next to the opponent's pieces, the same as
+    /// `analysis::potential_mobility`.
+    pub(crate) fn potential_mobility(&self, player: Player) -> usize {
+        (!self.occupied() & neighbours(self.pieces[player.opponent().index()])).count_ones() as usize
+    }
+
+    /// The number of the player's pieces next to a free square, the same as the length of
+    /// `analysis::frontier_discs`.
+    pub(crate) fn count_frontier_discs(&self, player: Player) -> usize {
+        (self.pieces[player.index()] & neighbours(!self.occupied())).count_ones() as usize
+    }
+
+    /// A hash of the pieces and the side to move. Unlike a zobrist hash it is worked out
+    /// from scratch each time, which is still cheap enough for ordering moves in a search.
+    pub(crate) fn position_key(&self) -> u64 {
+        let [black, white] = self.pieces;
+        let mixed = black.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ white.rotate_left(32).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+        mixed ^ self.side_to_move.index() as u64
+    }
+
+    fn take_back(&mut self) -> Option<BitMove> {
+        let last_move = self.history.pop()?;
+        self.remove_move(last_move);
+        self.undone_moves.push(last_move);
+        Some(last_move)
+    }
+
+    fn apply_move(&mut self, new_move: BitMove) {
+        let (own, opponent) = (new_move.player.index(), new_move.player.opponent().index());
+        self.pieces[own] |= new_move.square | new_move.flips;
+        self.pieces[opponent] &= !new_move.flips;
+        self.side_to_move = new_move.player.opponent();
+    }
+
+    fn remove_move(&mut self, old_move: BitMove) {
+        let (own, opponent) = (old_move.player.index(), old_move.player.opponent().index());
+        self.pieces[own] &= !(old_move.square | old_move.flips);
+        self.pieces[opponent] |= old_move.flips;
+        self.side_to_move = old_move.player;
+    }
+
+    pub(super) fn occupied(&self) -> u64 {
+        self.pieces[0] | self.pieces[1]
+    }
+
+    pub(super) fn legal_moves_mask(&self, player: Player) -> u64 {
+        let (own, opponent) = (self.pieces[player.index()], self.pieces[player.opponent().index()]);
+        let empty = !self.occupied();
+        let mut moves = 0;
+
+        for (shift_by, mask) in DIRECTIONS {
+            let mut candidates = shift(own, shift_by, mask) & opponent;
+            for _ in 0..5 {
+                candidates |= shift(candidates, shift_by, mask) & opponent;
+            }
+            moves |= shift(candidates, shift_by, mask) & empty;
+        }
+        moves
+    }
+
+    fn flips_mask(&self, bit: u64, player: Player) -> u64 {
+        let (own, opponent) = (self.pieces[player.index()], self.pieces[player.opponent().index()]);
+        let mut flips = 0;
+
+        for (shift_by, mask) in DIRECTIONS {
+            let mut ray = 0;
+            let mut cursor = shift(bit, shift_by, mask);
+
+            while cursor & opponent != 0 {
+                ray |= cursor;
+                cursor = shift(cursor, shift_by, mask);
+            }
+
+            if cursor & own != 0 {
+                flips |= ray;
+            }
+        }
+        flips
+    }
+}
+
+fn bits_to_squares(mut bits: u64) -> Vec<Square> {
+    let mut squares: Vec<Square> = Vec::with_capacity(bits.count_ones() as usize);
+
+    while bits != 0 {
+        squares.push(Square::from_index(bits.trailing_zeros() as usize, 8));
+        bits &= bits - 1;
+    }
+    squares
+}
+
+fn square_bit(square: Square) -> Option<u64> {
+    if square.rank() > 7 || square.file() > 7 {
+        return None
+    }
+    Some(1 << square.index(8))
+}
+
+/// Every square next to at least one of the given squares.
+fn neighbours(bits: u64) -> u64 {
+    DIRECTIONS.iter().fold(0, |neighbours, (shift_by, mask)| neighbours | shift(bits, *shift_by, *mask))
+}
+
+fn shift(bits: u64, shift_by: i32, mask: u64) -> u64 {
+    if shift_by > 0 {
+        (bits << shift_by) & mask
+    } else {
+        (bits >> -shift_by) & mask
+    }
+}
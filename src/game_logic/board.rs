@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+
+use super::{BitBoard, Move, OthelloBoard, Player, Square};
+
+/// The rules of the game as played on a board, shared by `OthelloBoard` and `BitBoard`,
+/// so that code written against it runs on either. A `BitBoard` plays the same moves as
+/// an `OthelloBoard` for any standard 8x8 position, only faster, but has no sizes,
+/// blocked squares, handicaps or free opening.
+pub trait Board: Clone {
+    fn size(&self) -> usize;
+
+    /// Whether the square lies on this board.
+    fn contains(&self, square: Square) -> bool {
+        square.rank() < self.size() && square.file() < self.size()
+    }
+
+    /// Every square of the board, rank by rank.
+    fn squares(&self) -> impl Iterator<Item = Square> {
+        let size = self.size();
+        (0..size * size).map(move |index| Square::from_index(index, size))
+    }
+
+    fn side_to_move(&self) -> Player;
+    fn get_piece_at(&self, square: Square) -> Option<Player>;
+    fn is_blocked(&self, square: Square) -> bool;
+    fn in_opening_phase(&self) -> bool;
+    /// A Zobrist hash of the pieces and the side to move. The same position gets the
+    /// same hash on either board.
+    fn zobrist_hash(&self) -> u64;
+
+    fn set_piece(&mut self, square: Square, player: Player) -> Result<(), &'static str>;
+    /// The pieces a move would flip, in board order.
+    fn flips_for(&self, square: Square, player: Player) -> Result<Vec<Square>, &'static str>;
+    fn legal_moves(&self, player: Player) -> Vec<Square>;
+    fn pass(&mut self) -> Result<(), &'static str>;
+    fn must_pass(&self, player: Player) -> bool;
+    fn undo(&mut self) -> Option<Move>;
+    fn redo(&mut self) -> Option<Move>;
+    /// How many moves have been played and not taken back.
+    fn moves_played(&self) -> usize;
+
+    fn is_game_over(&self) -> bool {
+        self.legal_moves(Player::Black).is_empty() && self.legal_moves(Player::White).is_empty()
+    }
+
+    /// Returns how many pieces black and white have on the board, in that order.
+    fn count_pieces(&self) -> (usize, usize);
+
+    /// The number of squares still free to hold a piece, leaving out blocked squares.
+    fn count_empty_squares(&self) -> usize {
+        self.squares()
+            .filter(|square| self.get_piece_at(*square).is_none() && !self.is_blocked(*square))
+            .count()
+    }
+
+    /// Returns black's and white's score with empty squares awarded to the winner, or split
+    /// evenly on a draw, where an odd one is left out so that the draw stands.
+    fn final_score(&self) -> (usize, usize) {
+        let (black_pieces, white_pieces) = self.count_pieces();
+        let empty_squares = self.count_empty_squares();
+
+        match black_pieces.cmp(&white_pieces) {
+            Ordering::Greater => (black_pieces + empty_squares, white_pieces),
+            Ordering::Less => (black_pieces, white_pieces + empty_squares),
+            Ordering::Equal => (black_pieces + empty_squares / 2, white_pieces + empty_squares / 2)
+        }
+    }
+
+    fn perft(&self, depth: u32) -> u64;
+}
+
+impl Board for OthelloBoard {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn contains(&self, square: Square) -> bool {
+        self.contains(square)
+    }
+
+    fn side_to_move(&self) -> Player {
+        self.side_to_move()
+    }
+
+    fn get_piece_at(&self, square: Square) -> Option<Player> {
+        self.get_piece_at(square)
+    }
+
+    fn is_blocked(&self, square: Square) -> bool {
+        self.is_blocked(square)
+    }
+
+    fn in_opening_phase(&self) -> bool {
+        self.in_opening_phase()
+    }
+
+    fn zobrist_hash(&self) -> u64 {
+        self.zobrist_hash()
+    }
+
+    fn set_piece(&mut self, square: Square, player: Player) -> Result<(), &'static str> {
+        self.set_piece(square, player)
+    }
+
+    fn flips_for(&self, square: Square, player: Player) -> Result<Vec<Square>, &'static str> {
+        self.flips_for(square, player)
+    }
+
+    fn legal_moves(&self, player: Player) -> Vec<Square> {
+        self.legal_moves(player)
+    }
+
+    fn pass(&mut self) -> Result<(), &'static str> {
+        self.pass()
+    }
+
+    fn must_pass(&self, player: Player) -> bool {
+        self.must_pass(player)
+    }
+
+    fn undo(&mut self) -> Option<Move> {
+        self.undo()
+    }
+
+    fn redo(&mut self) -> Option<Move> {
+        self.redo()
+    }
+
+    fn moves_played(&self) -> usize {
+        self.history().len()
+    }
+
+    fn count_pieces(&self) -> (usize, usize) {
+        self.count_pieces()
+    }
+
+    fn perft(&self, depth: u32) -> u64 {
+        self.perft(depth)
+    }
+}
+
+impl Board for BitBoard {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn side_to_move(&self) -> Player {
+        self.side_to_move()
+    }
+
+    fn get_piece_at(&self, square: Square) -> Option<Player> {
+        self.get_piece_at(square)
+    }
+
+    fn is_blocked(&self, _square: Square) -> bool {
+        false
+    }
+
+    fn in_opening_phase(&self) -> bool {
+        false
+    }
+
+    fn zobrist_hash(&self) -> u64 {
+        self.zobrist_hash()
+    }
+
+    fn set_piece(&mut self, square: Square, player: Player) -> Result<(), &'static str> {
+        self.set_piece(square, player)
+    }
+
+    fn flips_for(&self, square: Square, player: Player) -> Result<Vec<Square>, &'static str> {
+        self.flips_for(square, player)
+    }
+
+    fn legal_moves(&self, player: Player) -> Vec<Square> {
+        self.legal_moves(player)
+    }
+
+    fn pass(&mut self) -> Result<(), &'static str> {
+        self.pass()
+    }
+
+    fn must_pass(&self, player: Player) -> bool {
+        self.must_pass(player)
+    }
+
+    fn undo(&mut self) -> Option<Move> {
+        self.undo()
+    }
+
+    fn redo(&mut self) -> Option<Move> {
+        self.redo()
+    }
+
+    fn moves_played(&self) -> usize {
+        self.moves_played()
+    }
+
+    // Both checks come down to a few bit operations here, much faster than the defaults.
+    fn is_game_over(&self) -> bool {
+        self.legal_moves_mask(Player::Black) == 0 && self.legal_moves_mask(Player::White) == 0
+    }
+
+    fn count_pieces(&self) -> (usize, usize) {
+        self.count_pieces()
+    }
+
+    fn count_empty_squares(&self) -> usize {
+        self.occupied().count_zeros() as usize
+    }
+
+    fn perft(&self, depth: u32) -> u64 {
+        self.perft(depth)
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+
+use super::{Player, Square};
+
+/// Corner pieces given to the weaker player before the first move, so that players
+/// of different strength can still have a close game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Handicap {
+    player: Player,
+    corners: usize
+}
+
+impl Handicap {
+    pub const MAX_CORNERS: usize = 4;
+
+    pub fn new(player: Player, corners: usize) -> Result<Self, &'static str> {
+        if !(1..=Self::MAX_CORNERS).contains(&corners) {
+            return Err("Invalid handicap given. A handicap must give between 1 and 4 corners.")
+        }
+        Ok(Handicap { player, corners })
+    }
+
+    /// Every possible handicap, by player and then by number of corners.
+    pub fn all() -> impl Iterator<Item = Handicap> {
+        Player::ALL.into_iter().flat_map(|player| {
+            (1..=Self::MAX_CORNERS).map(move |corners| Handicap { player, corners })
+        })
+    }
+
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn corners(&self) -> usize {
+        self.corners
+    }
+
+    /// The corners given on a board of the given size. Opposite corners are handed out
+    /// first, starting with a1 and the bottom right corner.
+    pub fn squares(&self, size: usize) -> Vec<Square> {
+        let last = size - 1;
+        [(0, 0), (last, last), (0, last), (last, 0)]
+            .into_iter()
+            .take(self.corners)
+            .map(|(rank, file)| Square::from_index(rank * size + file, size))
+            .collect()
+    }
+}
+
+/// Describes the handicap, such as `white starts with 2 corners`.
+impl fmt::Display for Handicap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.corners {
+            1 => write!(f, "{} starts with 1 corner", self.player),
+            corners => write!(f, "{} starts with {} corners", self.player, corners)
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::{Rng, Square};
+
+/// Starting layouts with permanently blocked squares, which can't hold pieces
+/// and stop any line of flanked pieces that runs into them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BoardLayout {
+    #[default]
+    Open,
+    /// The board with its four corners cut off, leaving an octagon.
+    Octagon,
+    /// Randomly placed obstacles, mirrored through the centre so neither side is favoured.
+    RandomObstacles
+}
+
+impl BoardLayout {
+    pub const ALL: [BoardLayout; 3] = [BoardLayout::Open, BoardLayout::Octagon, BoardLayout::RandomObstacles];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BoardLayout::Open => "Open board",
+            BoardLayout::Octagon => "Octagon",
+            BoardLayout::RandomObstacles => "Random obstacles"
+        }
+    }
+
+    /// Whether games on this layout can start from a random opening. Only the open board
+    /// can, as blocked squares could land on the opening's moves.
+    pub fn allows_random_opening(&self) -> bool {
+        *self == BoardLayout::Open
+    }
+
+    /// Lists the blocked squares of this layout on a board of the given size.
+    /// The seed is only used by random layouts.
+    pub fn blocked_squares(&self, size: usize, seed: u64) -> Vec<Square> {
+        match self {
+            BoardLayout::Open => Vec::new(),
+            BoardLayout::Octagon => octagon_corners(size),
+            BoardLayout::RandomObstacles => random_obstacles(size, seed)
+        }
+    }
+}
+
+fn octagon_corners(size: usize) -> Vec<Square> {
+    let last = size - 1;
+    let mut blocked: Vec<Square> = Vec::new();
+
+    for (rank, file) in [(0, 0), (0, last), (last, 0), (last, last)] {
+        let towards_centre = |x: usize| if x == 0 { 1 } else { x - 1 };
+        blocked.push(Square::from_index(rank * size + file, size));
+        blocked.push(Square::from_index(towards_centre(rank) * size + file, size));
+        blocked.push(Square::from_index(rank * size + towards_centre(file), size));
+    }
+    blocked
+}
+
+/// Obstacles never land on the centre or on black's four opening moves, so the game
+/// can always get going. Those moves are mirrored onto each other through the centre,
+/// just like the obstacles.
+fn random_obstacles(size: usize, seed: u64) -> Vec<Square> {
+    let mut rng = Rng::new(seed);
+    let mut blocked: Vec<Square> = Vec::new();
+    let centre = size / 2;
+    let opening_moves = [
+        (centre - 2, centre - 1), (centre - 1, centre - 2), (centre, centre + 1), (centre + 1, centre)
+    ];
+
+    while blocked.len() < size {
+        let (rank, file) = (rng.below(size), rng.below(size));
+        let square = Square::from_index(rank * size + file, size);
+        let in_centre = (centre - 1..=centre).contains(&rank) && (centre - 1..=centre).contains(&file);
+        if in_centre || opening_moves.contains(&(rank, file)) || blocked.contains(&square) {
+            continue;
+        }
+
+        let mirrored = Square::from_index((size - 1 - rank) * size + (size - 1 - file), size);
+        blocked.push(square);
+        blocked.push(mirrored);
+    }
+    blocked
+}
@@ -1,3 +1,26 @@
 mod othello_board;
+mod board;
+mod player;
+mod square;
+mod bit_board;
+mod zobrist;
+mod ruleset;
+mod rng;
+mod layout;
+mod symmetry;
+mod handicap;
+mod openings;
+pub mod analysis;
 
-pub use othello_board::OthelloBoard;
+pub use othello_board::{OthelloBoard, Move, MIN_BOARD_SIZE, MAX_BOARD_SIZE};
+pub use board::Board;
+pub use player::Player;
+pub use square::Square;
+pub use bit_board::BitBoard;
+pub use ruleset::Ruleset;
+pub use rng::Rng;
+pub use layout::BoardLayout;
+pub use symmetry::Symmetry;
+pub use handicap::Handicap;
+pub use openings::OpeningList;
+pub(crate) use openings::parse_moves;
@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{OthelloBoard, Rng, Square};
+
+/// A list of short openings played out before a game starts, so that games begin from
+/// a varied but balanced position instead of a memorised one, as in XOT matches.
+#[derive(Clone, Debug)]
+pub struct OpeningList {
+    openings: Vec<Vec<Square>>
+}
+
+impl OpeningList {
+    /// The openings bundled in `assets/xot_openings.txt`.
+    pub fn standard() -> Self {
+        include_str!("../../assets/xot_openings.txt").parse()
+            .expect("The bundled openings are all legal.")
+    }
+
+    /// Reads and checks a list of openings from a text file, in the format described
+    /// for `from_str`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        fs::read_to_string(path)
+            .map_err(|_| "Could not read the openings file.")?
+            .parse()
+    }
+
+    pub fn openings(&self) -> &[Vec<Square>] {
+        &self.openings
+    }
+
+    /// Picks one of the openings at random.
+    pub fn pick(&self, rng: &mut Rng) -> &[Square] {
+        &self.openings[rng.below(self.openings.len())]
+    }
+}
+
+/// Parses one opening per line, written as its moves run together such as `f5d6c3d3`,
+/// optionally separated by spaces. Blank lines and lines starting with `#` are skipped.
+/// Every opening is played out on a standard board to make sure it is legal.
+impl FromStr for OpeningList {
+    type Err = &'static str;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut openings: Vec<Vec<Square>> = Vec::new();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let opening = parse_moves(line)?;
+            let mut board = OthelloBoard::new();
+            for square in &opening {
+                board.set_piece(*square, board.side_to_move())
+                    .map_err(|_| "Invalid opening given. One of its moves is illegal.")?;
+            }
+            openings.push(opening);
+        }
+
+        if openings.is_empty() {
+            return Err("The list of openings is empty.")
+        }
+        Ok(OpeningList { openings })
+    }
+}
+
+/// Splits a run of moves such as `f5d6c3` into its squares.
+pub(crate) fn parse_moves(line: &str) -> Result<Vec<Square>, &'static str> {
+    let moves: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    if !moves.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err("Invalid moves given. Moves must be written as squares such as 'f5'.")
+    }
+
+    let starts: Vec<usize> = moves.char_indices()
+        .filter(|(_, c)| c.is_ascii_alphabetic())
+        .map(|(index, _)| index)
+        .collect();
+
+    starts.iter().enumerate().map(|(n, start)| {
+        let end = starts.get(n + 1).copied().unwrap_or(moves.len());
+        moves[*start..end].parse()
+    }).collect()
+}
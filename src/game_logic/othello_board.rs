@@ -1,143 +1,529 @@
-use std::cmp;
-use crate::Position;
+use std::fmt;
+use std::str::FromStr;
+use super::{zobrist, Handicap, Player, Square, Symmetry};
 
-#[derive(Copy, Clone, Debug)]
-struct OthelloPiece{
-    state: u8
+/// A single placement made on the board, with every piece it flipped,
+/// so that it can be taken back or replayed later on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Move {
+    pub player: Player,
+    pub square: Square,
+    /// The flipped pieces in board order, rank by rank, whichever board played the move.
+    pub flipped: Vec<Square>
 }
 
-impl OthelloPiece{
-    fn new(which_player: u8) -> Self {
-        if which_player != 0 && which_player != 1 {
-            panic!("Received an invalid state for an Othello Piece. A piece must always be
-            in a binary state, representing a piece for either player.");
-        }
-        OthelloPiece { state: which_player }
-    }
-}
+pub const MIN_BOARD_SIZE: usize = 4;
+pub const MAX_BOARD_SIZE: usize = 16;
 
-impl PartialEq for OthelloPiece {
-    fn eq(&self, other: &Self) -> bool {
-        self.state == other.state
-    }
-}
+// rank and file steps for the eight directions a line of flanked pieces can run in
+pub(super) const DIRECTIONS: [(isize, isize); 8] = [
+    (1, 0), (-1, 0), (0, 1), (0, -1),
+    (1, 1), (1, -1), (-1, 1), (-1, -1),
+];
 
+#[derive(Clone)]
 pub struct OthelloBoard{
-    board_state: [[Option<OthelloPiece>; 8]; 8],
-    last_board_state: [[Option<OthelloPiece>; 8]; 8]
+    board_state: Vec<Option<Player>>,
+    blocked: Vec<bool>,
+    size: usize,
+    // whether the game started from an empty board, placing the centre pieces first
+    free_opening: bool,
+    side_to_move: Player,
+    hash: u64,
+    history: Vec<Move>,
+    undone_moves: Vec<Move>
+}
+
+impl Default for OthelloBoard {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl OthelloBoard{
     pub fn new() -> Self {
-        let mut board = [[None; 8]; 8];
-        board[3][3] = Some(OthelloPiece::new(1));
-        board[3][4] = Some(OthelloPiece::new(0));
-        board[4][3] = Some(OthelloPiece::new(0));
-        board[4][4] = Some(OthelloPiece::new(1));
+        Self::with_size(8).expect("The standard board size is always valid.")
+    }
+
+    /// Creates a square board with `size` ranks and files, with the four starting
+    /// pieces in its centre. The size must be even so that the centre is well defined.
+    pub fn with_size(size: usize) -> Result<Self, &'static str> {
+        validate_size(size)?;
+
+        let mut board = vec![None; size * size];
+        let centre = size / 2;
+        board[(centre-1) * size + centre-1] = Some(Player::White);
+        board[(centre-1) * size + centre] = Some(Player::Black);
+        board[centre * size + centre-1] = Some(Player::Black);
+        board[centre * size + centre] = Some(Player::White);
+
+        Ok(OthelloBoard::from_state(board, size, Player::Black))
+    }
+
+    /// Creates a board without any pieces, for the free opening of classic Reversi.
+    /// Until the four centre squares are filled, players take turns placing pieces
+    /// on them without having to flip anything.
+    pub fn empty(size: usize) -> Result<Self, &'static str> {
+        validate_size(size)?;
+        let mut board = OthelloBoard::from_state(vec![None; size * size], size, Player::Black);
+        board.free_opening = true;
+        Ok(board)
+    }
+
+    fn from_state(board_state: Vec<Option<Player>>, size: usize, side_to_move: Player) -> Self {
+        let mut board = OthelloBoard {
+            size,
+            board_state,
+            blocked: vec![false; size * size],
+            free_opening: false,
+            side_to_move: Player::Black,
+            hash: 0,
+            history: Vec::new(),
+            undone_moves: Vec::new()
+        };
+
+        for (index, piece) in board.board_state.iter().enumerate() {
+            if let Some(player) = piece {
+                board.hash ^= zobrist::piece_key(Square::from_index(index, size), *player);
+            }
+        }
+        board.set_side_to_move(side_to_move);
+        board
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the square lies on this board.
+    pub fn contains(&self, square: Square) -> bool {
+        square.rank() < self.size && square.file() < self.size
+    }
+
+    /// Every square of the board, rank by rank.
+    pub fn squares(&self) -> impl Iterator<Item = Square> {
+        let size = self.size;
+        (0..size * size).map(move |index| Square::from_index(index, size))
+    }
+
+    pub fn side_to_move(&self) -> Player {
+        self.side_to_move
+    }
+
+    /// A Zobrist hash of the pieces on the board and the side to move, kept up to date
+    /// as moves are played, undone or passed. Equal positions always share a hash.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Permanently blocks the given squares, which can never hold a piece and stop
+    /// any line of flanked pieces running into them. Squares already holding a piece
+    /// and the four centre squares can't be blocked.
+    pub fn block_squares(&mut self, squares: &[Square]) -> Result<(), &'static str> {
+        for square in squares {
+            if !self.contains(*square) {
+                return Err("Invalid blocked square given. It lies outside of the game board.")
+            }
+
+            if self.is_centre_square(*square) || self.board_state[self.index(*square)].is_some() {
+                return Err("Invalid blocked square given. The centre squares and squares holding \
+                a piece can't be blocked.")
+            }
+        }
+
+        for square in squares {
+            let index = self.index(*square);
+            self.blocked[index] = true;
+        }
+        Ok(())
+    }
 
-        OthelloBoard { board_state: board, last_board_state: board.clone() }
+    /// Places the handicap's corner pieces on the board as part of the starting position,
+    /// so they can't be undone. This has to happen before the first move, and fails when
+    /// any of the corners is blocked or already holds a piece.
+    pub fn place_handicap(&mut self, handicap: Handicap) -> Result<(), &'static str> {
+        if !self.history.is_empty() {
+            return Err("A handicap can only be given before the first move.")
+        }
+
+        let corners = handicap.squares(self.size);
+        for square in &corners {
+            if self.blocked[self.index(*square)] || self.board_state[self.index(*square)].is_some() {
+                return Err("Invalid handicap given. The corners it needs are blocked or already \
+                hold a piece.")
+            }
+        }
+
+        for square in corners {
+            let index = self.index(square);
+            self.board_state[index] = Some(handicap.player());
+            self.hash ^= zobrist::piece_key(square, handicap.player());
+        }
+        Ok(())
     }
 
-    pub fn get_piece_at(&self, rank: usize, file: usize) -> Option<u8>{
-        self.board_state[file][rank].map(|piece| piece.state)
+    pub fn is_blocked(&self, square: Square) -> bool {
+        self.contains(square) && self.blocked[self.index(square)]
     }
 
-    pub fn set_piece(&mut self, rank: usize, file: usize, which_player: u8) -> Result<(), &'static str> {
-        if rank > 7 || file > 7 {
+    pub fn get_piece_at(&self, square: Square) -> Option<Player> {
+        match self.contains(square) {
+            true => self.board_state[self.index(square)],
+            false => None
+        }
+    }
+
+    pub fn set_piece(&mut self, square: Square, player: Player) -> Result<(), &'static str> {
+        if player != self.side_to_move {
+            return Err("It is not this player's turn to move.")
+        }
+
+        let should_flip = self.flips_for(square, player)?;
+        let new_move = Move { player, square, flipped: should_flip };
+        self.apply_move(&new_move);
+        self.history.push(new_move);
+        self.undone_moves.clear();
+
+        Ok(())
+    }
+
+    /// Returns the pieces the given player would flip by placing a piece on the square,
+    /// without touching the board, or why that move would be illegal. Whose turn it is
+    /// doesn't matter here. Moves in the free opening are legal but flip nothing.
+    pub fn flips_for(&self, square: Square, player: Player) -> Result<Vec<Square>, &'static str> {
+        if !self.contains(square) {
             return Err("Invalid piece position given. Either the rank or the file are outside \
-            of the game board's upper bounds. Maximum rank: h, maximum file: 8.");
+            of the game board's upper bounds.");
         }
 
-        if let Some(_) = self.board_state[file][rank] {
+        if self.board_state[self.index(square)].is_some() {
             return Err("There already is a piece at the given position. Pieces must be placed on \
             empty squares.")
         }
-        
-        self.last_board_state = self.board_state.clone();
-        let new_piece = OthelloPiece::new(which_player);
-        self.board_state[file][rank] = Some(new_piece);
-        self.flip_pieces_if_needed(rank, file);
 
+        if self.blocked[self.index(square)] {
+            return Err("The given square is blocked. No pieces can be placed on it.")
+        }
+
+        if self.in_opening_phase() {
+            if !self.is_centre_square(square) {
+                return Err("The opening is not over yet. Pieces must be placed on the four centre \
+                squares first.")
+            }
+            return Ok(Vec::new())
+        }
+
+        let should_flip = self.check_for_flanks(square, player);
+        if should_flip.is_empty() {
+            return Err("Invalid move. A piece must outflank at least one of the opponent's \
+            pieces in a straight line.")
+        }
+        Ok(should_flip)
+    }
+
+    /// Takes back the last move played, returning it. Undone moves can be replayed
+    /// with `redo` until a new piece is placed on the board.
+    pub fn undo(&mut self) -> Option<Move> {
+        let last_move = self.history.pop()?;
+        let index = self.index(last_move.square);
+
+        self.board_state[index] = None;
+        self.hash ^= zobrist::piece_key(last_move.square, last_move.player);
+        for square in &last_move.flipped {
+            self.flip_piece(*square, last_move.player.opponent());
+        }
+        self.set_side_to_move(last_move.player);
+
+        self.undone_moves.push(last_move.clone());
+        Some(last_move)
+    }
+
+    /// Replays the last move taken back by `undo`, returning it.
+    pub fn redo(&mut self) -> Option<Move> {
+        let undone_move = self.undone_moves.pop()?;
+        self.apply_move(&undone_move);
+        self.history.push(undone_move.clone());
+        Some(undone_move)
+    }
+
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Returns a copy of the position rotated or reflected by the given symmetry, with the
+    /// same side to move. The move history isn't carried over, so the copy can't be undone.
+    pub fn transformed(&self, symmetry: Symmetry) -> OthelloBoard {
+        let mut board_state = vec![None; self.size * self.size];
+        let mut blocked = vec![false; self.size * self.size];
+
+        for square in self.squares() {
+            let (from, to) = (self.index(square), symmetry.apply(square, self.size).index(self.size));
+            board_state[to] = self.board_state[from];
+            blocked[to] = self.blocked[from];
+        }
+
+        let mut board = OthelloBoard::from_state(board_state, self.size, self.side_to_move);
+        board.blocked = blocked;
+        board.free_opening = self.free_opening;
+        board
+    }
+
+    /// Picks the same representative among all eight symmetric versions of a position,
+    /// so that symmetric positions can be stored once. Returns it along with the symmetry
+    /// that maps it back onto this board: squares of the canonical board, such as a move
+    /// looked up for it, are turned into squares of this board with `symmetry.apply`.
+    pub fn canonical(&self) -> (OthelloBoard, Symmetry) {
+        Symmetry::ALL.iter()
+            .map(|symmetry| (self.transformed(*symmetry), symmetry.inverse()))
+            .min_by_key(|(board, _)| board.to_string())
+            .expect("There is always at least the identity symmetry.")
+    }
+
+    /// Returns every empty square where the given player may legally place a piece,
+    /// i.e. every square from which at least one of the opponent's pieces would be flipped,
+    /// or every empty centre square while the opening is not over.
+    pub fn legal_moves(&self, player: Player) -> Vec<Square> {
+        self.squares().filter(|square| self.is_legal_move(*square, player)).collect()
+    }
+
+    /// Whether the board is still in the free opening, with some of the four centre
+    /// squares empty. Only boards created with `empty` have this phase, so other boards
+    /// with an empty centre, such as parsed positions, are played by the usual rules.
+    pub fn in_opening_phase(&self) -> bool {
+        if !self.free_opening {
+            return false
+        }
+
+        let centre = self.size / 2;
+        [(centre-1, centre-1), (centre-1, centre), (centre, centre-1), (centre, centre)]
+            .iter()
+            .any(|(rank, file)| self.board_state[rank * self.size + file].is_none())
+    }
+
+    /// Hands the turn to the opponent when the side to move has no legal moves.
+    /// Passing is never optional, so this fails whenever a move is available.
+    pub fn pass(&mut self) -> Result<(), &'static str> {
+        if !self.must_pass(self.side_to_move) {
+            return Err("A player may only pass when they have no legal moves.")
+        }
+
+        self.set_side_to_move(self.side_to_move.opponent());
         Ok(())
     }
 
+    /// Whether the given player is forced to pass, having no legal moves while
+    /// their opponent still has some.
+    pub fn must_pass(&self, player: Player) -> bool {
+        !self.has_legal_move(player) && self.has_legal_move(player.opponent())
+    }
+
+    /// The game is over once neither player has a legal move left, which includes
+    /// a full board and a board where one of the players has been wiped out.
+    /// Returns how many pieces black and white have on the board, in that order.
     pub fn count_pieces(&self) -> (usize, usize) {
-        let mut p1_pieces: usize = 0;
-        let mut p2_pieces: usize = 0;
-
-        let _: Vec<_> = self.board_state.as_flattened().into_iter().map(|piece| {
-            if let Some(piece) = piece {
-                match piece.state {
-                    0 => p1_pieces += 1,
-                    1 => p2_pieces += 1,
-                    _ => panic!("Unexpected value received for an Othello Piece.")
-                };
+        let mut black_pieces: usize = 0;
+        let mut white_pieces: usize = 0;
+
+        for player in self.board_state.iter().flatten() {
+            match player {
+                Player::Black => black_pieces += 1,
+                Player::White => white_pieces += 1
+            };
+        }
+
+        (black_pieces, white_pieces)
+    }
+
+    /// Counts the move paths of the given length from this position, the usual way of
+    /// checking move generation against published node counts. A forced pass counts as
+    /// a move, and a game that ends before the full length counts as a single leaf.
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.clone().count_leaves(depth)
+    }
+
+    fn count_leaves(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1
+        }
+
+        let player = self.side_to_move;
+        let moves = self.legal_moves(player);
+        if moves.is_empty() {
+            if !self.has_legal_move(player.opponent()) {
+                return 1
             }
-        }).collect();
 
-        (p1_pieces, p2_pieces)
+            self.set_side_to_move(player.opponent());
+            let leaves = self.count_leaves(depth - 1);
+            self.set_side_to_move(player);
+            return leaves
+        }
+
+        moves.into_iter().map(|square| {
+            self.set_piece(square, player).expect("Legal moves can always be played.");
+            let leaves = self.count_leaves(depth - 1);
+            self.undo();
+            leaves
+        }).sum()
     }
 
-    pub fn print_board(&self) {
-        for rank in self.board_state {
-            println!("{rank:?}")
-        } 
+    fn index(&self, square: Square) -> usize {
+        square.index(self.size)
     }
 
-    pub fn revert_to_last_state(&mut self) {
-        self.board_state = self.last_board_state;
+    fn has_legal_move(&self, player: Player) -> bool {
+        self.squares().any(|square| self.is_legal_move(square, player))
     }
 
-    fn flip_pieces_if_needed(&mut self, rank: usize, file: usize) {
-        let should_flip = self.check_for_flanks(rank, file);
-        let current_state = self.board_state[file][rank].unwrap().state;
+    fn is_legal_move(&self, square: Square, player: Player) -> bool {
+        let index = self.index(square);
+        if self.board_state[index].is_some() || self.blocked[index] {
+            return false
+        }
 
-        for (rank, file) in should_flip {
-            self.board_state[file][rank] = Some(OthelloPiece { state: current_state })
+        if self.in_opening_phase() {
+            return self.is_centre_square(square)
         }
+        !self.check_for_flanks(square, player).is_empty()
     }
 
-    fn check_for_flanks(&mut self, rank: usize, file: usize) -> Vec<Position> {
-        let mut should_flip: Vec<Position> = Vec::new();
-        let current_piece = self.board_state[file][rank];
+    fn is_centre_square(&self, square: Square) -> bool {
+        let centre = self.size / 2;
+        (centre-1..=centre).contains(&square.rank()) && (centre-1..=centre).contains(&square.file())
+    }
 
-        for direction in self.cast_rays(rank, file) {
-            for (i, (rank, file)) in direction.iter().enumerate() {
-                if let None = self.board_state[*file][*rank] {
-                    break;
-                }
+    fn apply_move(&mut self, new_move: &Move) {
+        let index = self.index(new_move.square);
+
+        self.board_state[index] = Some(new_move.player);
+        self.hash ^= zobrist::piece_key(new_move.square, new_move.player);
+        for square in &new_move.flipped {
+            self.flip_piece(*square, new_move.player);
+        }
+        self.set_side_to_move(new_move.player.opponent());
+    }
+
+    fn flip_piece(&mut self, square: Square, to_player: Player) {
+        let index = self.index(square);
+        self.board_state[index] = Some(to_player);
+        self.hash ^= zobrist::piece_key(square, to_player.opponent())
+            ^ zobrist::piece_key(square, to_player);
+    }
 
-                if current_piece == self.board_state[*file][*rank] {
-                    should_flip.extend_from_slice(&direction[..i]);
-                    break;
+    fn set_side_to_move(&mut self, player: Player) {
+        if self.side_to_move != player {
+            self.hash ^= zobrist::side_to_move_key();
+        }
+        self.side_to_move = player;
+    }
+
+    // Blocked squares never hold a piece, so rays stop at them just like at empty squares.
+    // Pieces are collected along each ray as it is walked, and dropped again unless the
+    // ray ends in one of the player's own pieces. They are sorted at the end to come out
+    // in board order like a `BitBoard`'s.
+    fn check_for_flanks(&self, square: Square, player: Player) -> Vec<Square> {
+        let mut should_flip: Vec<Square> = Vec::new();
+
+        for (rank_delta, file_delta) in DIRECTIONS {
+            let flipped_before = should_flip.len();
+            let mut next = square.offset(rank_delta, file_delta, self.size);
+            let mut flanked = false;
+
+            while let Some(square) = next {
+                match self.board_state[self.index(square)] {
+                    None => break,
+                    Some(owner) if owner == player => {
+                        flanked = true;
+                        break;
+                    },
+                    Some(_) => should_flip.push(square)
                 }
+                next = square.offset(rank_delta, file_delta, self.size);
+            }
+
+            if !flanked {
+                should_flip.truncate(flipped_before);
             }
         }
+        should_flip.sort();
         should_flip
     }
+}
 
-    fn cast_rays(&self, rank: usize, file: usize) -> Vec<Vec<Position>> {
-        let mut hit_rays: Vec<Vec<Position>> = Vec::new();
+fn validate_size(size: usize) -> Result<(), &'static str> {
+    if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) || !size.is_multiple_of(2) {
+        return Err("Invalid board size. The board must have an even number of ranks and \
+        files, between 4 and 16.")
+    }
+    Ok(())
+}
 
-        // up, down, right, left
-        hit_rays.push((rank+1..8).map(|x| (x, file)).collect());
-        hit_rays.push((0..rank).rev().map(|x| (x, file)).collect());
-        hit_rays.push((file+1..8).map(|y| (rank, y)).collect());
-        hit_rays.push((0..file).rev().map(|y| (rank, y)).collect());
+/// Prints the board in the standard position notation: every square rank by rank,
+/// `X` for black, `O` for white and `-` for an empty square, then the side to move.
+/// Blocked squares, which standard boards don't have, are written as `#`.
+impl fmt::Display for OthelloBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, piece) in self.board_state.iter().enumerate() {
+            let symbol = match piece {
+                Some(Player::Black) => 'X',
+                Some(Player::White) => 'O',
+                None if self.blocked[index] => '#',
+                None => '-'
+            };
+            write!(f, "{symbol}")?;
+        }
 
-        // diagonals
-        let limit = cmp::min(7-rank, 7-file);
-        hit_rays.push((1..=limit).map(|x| (rank+x, file+x)).collect());
+        let side_to_move = match self.side_to_move {
+            Player::Black => 'X',
+            Player::White => 'O'
+        };
+        write!(f, " {side_to_move}")
+    }
+}
 
-        let limit = cmp::min(7-rank, file);
-        hit_rays.push((1..=limit).map(|x| (rank+x, file-x)).collect());
+/// Parses a board from the notation printed by `Display`. Whitespace is ignored,
+/// so the side to move may be separated from the squares or not. The board size is
+/// deduced from the number of squares, so a 6x6 board is written with 36 of them.
+impl FromStr for OthelloBoard {
+    type Err = &'static str;
 
-        let limit = cmp::min(rank, 7-file);
-        hit_rays.push((1..=limit).map(|x| (rank-x, file+x)).collect());
+    fn from_str(position: &str) -> Result<Self, Self::Err> {
+        let symbols: Vec<char> = position.chars().filter(|c| !c.is_whitespace()).collect();
+        let Some((side_to_move, squares)) = symbols.split_last() else {
+            return Err("Empty position string. A position needs a square board followed by the \
+            side to move.")
+        };
+
+        let size = (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).step_by(2)
+            .find(|size| size * size == squares.len())
+            .ok_or("Invalid position string length. A position needs a square board, such as \
+            64 squares for 8x8, followed by the side to move.")?;
+
+        let mut board = Vec::with_capacity(size * size);
+        let mut blocked: Vec<Square> = Vec::new();
+        for (index, symbol) in squares.iter().enumerate() {
+            board.push(match symbol {
+                'X' => Some(Player::Black),
+                'O' => Some(Player::White),
+                '-' => None,
+                '#' => {
+                    blocked.push(Square::from_index(index, size));
+                    None
+                },
+                _ => return Err("Invalid square in position string. Squares must be 'X' (black), \
+                'O' (white), '-' (empty) or '#' (blocked).")
+            });
+        }
 
-        let limit = cmp::min(rank, file);
-        hit_rays.push((1..=limit).map(|x| (rank-x, file-x)).collect());
+        let side_to_move = match side_to_move {
+            'X' => Player::Black,
+            'O' => Player::White,
+            _ => return Err("Invalid side to move in position string. It must be either 'X' \
+            (black) or 'O' (white).")
+        };
 
-        hit_rays
+        let mut board = OthelloBoard::from_state(board, size, side_to_move);
+        board.block_squares(&blocked)?;
+        Ok(board)
     }
 }
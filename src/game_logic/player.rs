@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// One of the two sides of a game. Black always moves first.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Player {
+    Black,
+    White
+}
+
+impl Player {
+    pub const ALL: [Player; 2] = [Player::Black, Player::White];
+
+    pub fn opponent(self) -> Player {
+        match self {
+            Player::Black => Player::White,
+            Player::White => Player::Black
+        }
+    }
+
+    /// Zero for black and one for white, for indexing per-player tables.
+    pub fn index(self) -> usize {
+        match self {
+            Player::Black => 0,
+            Player::White => 1
+        }
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Player::Black => write!(f, "black"),
+            Player::White => write!(f, "white")
+        }
+    }
+}
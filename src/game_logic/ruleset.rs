@@ -0,0 +1,55 @@
+use super::{Board, Player};
+
+/// How the winner of a finished game is decided.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Ruleset {
+    #[default]
+    Standard,
+    /// Reversed scoring, where the player left with the fewest pieces wins.
+    AntiOthello
+}
+
+impl Ruleset {
+    pub const ALL: [Ruleset; 2] = [Ruleset::Standard, Ruleset::AntiOthello];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ruleset::Standard => "Standard Othello",
+            Ruleset::AntiOthello => "Anti-Othello"
+        }
+    }
+
+    pub fn win_condition(&self) -> &'static str {
+        match self {
+            Ruleset::Standard => "The player with the most pieces on the board wins.",
+            Ruleset::AntiOthello => "The player with the fewest pieces on the board wins."
+        }
+    }
+
+    /// Returns black's and white's official score for the board. Standard games award
+    /// empty squares to the winner, while Anti-Othello games keep the raw piece counts,
+    /// since handing out empties there would count against the winner.
+    pub fn final_score(&self, board: &impl Board) -> (usize, usize) {
+        match self {
+            Ruleset::Standard => board.final_score(),
+            Ruleset::AntiOthello => board.count_pieces()
+        }
+    }
+
+    /// Returns the winning player given black's and white's scores, or `None` on a tie.
+    pub fn winner(&self, (black_score, white_score): (usize, usize)) -> Option<Player> {
+        if black_score == white_score {
+            return None
+        }
+
+        let (most, fewest) = match black_score > white_score {
+            true => (Player::Black, Player::White),
+            false => (Player::White, Player::Black)
+        };
+
+        match self {
+            Ruleset::Standard => Some(most),
+            Ruleset::AntiOthello => Some(fewest)
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::othello_board::MAX_BOARD_SIZE;
+
+/// A square on the board, addressed by its rank (row, `1` onwards from the top) and
+/// its file (column, `a` onwards from the left), both counted from zero. Squares are
+/// ordered rank by rank, the same order the position notation lists them in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square {
+    rank: usize,
+    file: usize
+}
+
+impl Square {
+    /// Creates a square from its zero-based rank and file. Whether the square actually lies
+    /// on a given board is checked by the board itself, as sizes vary between games.
+    pub fn new(rank: usize, file: usize) -> Result<Self, &'static str> {
+        if file >= MAX_BOARD_SIZE || rank >= MAX_BOARD_SIZE {
+            return Err("Invalid square given. Files and ranks can't go beyond the largest \
+            supported board.")
+        }
+        Ok(Square { rank, file })
+    }
+
+    pub fn file(&self) -> usize {
+        self.file
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub(crate) fn from_index(index: usize, size: usize) -> Self {
+        Square { rank: index / size, file: index % size }
+    }
+
+    pub(crate) fn index(&self, size: usize) -> usize {
+        self.rank * size + self.file
+    }
+
+    /// The square reached by moving the given number of ranks and files away,
+    /// if it still lies on a board of the given size.
+    pub(crate) fn offset(&self, rank_delta: isize, file_delta: isize, size: usize) -> Option<Self> {
+        let rank = self.rank.checked_add_signed(rank_delta).filter(|rank| *rank < size)?;
+        let file = self.file.checked_add_signed(file_delta).filter(|file| *file < size)?;
+        Some(Square { rank, file })
+    }
+}
+
+/// Prints the square in algebraic notation, such as `d3`.
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.file as u8) as char, self.rank + 1)
+    }
+}
+
+/// Parses a square written in algebraic notation, such as `d3` or `J10`.
+impl FromStr for Square {
+    type Err = &'static str;
+
+    fn from_str(square: &str) -> Result<Self, Self::Err> {
+        let mut chars = square.trim().chars();
+        let file = chars.next()
+            .filter(|letter| letter.is_ascii_alphabetic())
+            .map(|letter| (letter.to_ascii_lowercase() as u8 - b'a') as usize)
+            .ok_or("Invalid square notation. A square starts with the letter of its file.")?;
+
+        let rank: usize = chars.as_str().parse()
+            .ok()
+            .filter(|rank| *rank >= 1)
+            .ok_or("Invalid square notation. The file must be followed by a rank number, \
+            starting from 1.")?;
+
+        Square::new(rank - 1, file)
+    }
+}
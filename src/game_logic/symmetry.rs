@@ -0,0 +1,59 @@
+use super::Square;
+
+/// One of the eight symmetries of a square board: the rotations by quarter turns
+/// and the reflections through its middle lines and diagonals. Rotations turn clockwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors the files, swapping the left and right edges.
+    FlipFiles,
+    /// Mirrors the ranks, swapping the top and bottom edges.
+    FlipRanks,
+    /// Reflects through the diagonal running from a1, swapping files and ranks.
+    FlipDiagonal,
+    /// Reflects through the other diagonal.
+    FlipAntiDiagonal
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipFiles,
+        Symmetry::FlipRanks,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    /// The symmetry that undoes this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other
+        }
+    }
+
+    /// Maps a square of a board with the given size to where this symmetry moves it.
+    pub fn apply(self, square: Square, size: usize) -> Square {
+        let last = size - 1;
+        let (rank, file) = (square.rank(), square.file());
+
+        let (rank, file) = match self {
+            Symmetry::Identity => (rank, file),
+            Symmetry::Rotate90 => (file, last - rank),
+            Symmetry::Rotate180 => (last - rank, last - file),
+            Symmetry::Rotate270 => (last - file, rank),
+            Symmetry::FlipFiles => (rank, last - file),
+            Symmetry::FlipRanks => (last - rank, file),
+            Symmetry::FlipDiagonal => (file, rank),
+            Symmetry::FlipAntiDiagonal => (last - file, last - rank)
+        };
+        Square::from_index(rank * size + file, size)
+    }
+}
@@ -0,0 +1,25 @@
+use super::othello_board::MAX_BOARD_SIZE;
+use super::{Player, Square};
+
+const ZOBRIST_SEED: u64 = 0x4F54_4845_4C4C_4F21;
+const SIDE_TO_MOVE_INDEX: usize = MAX_BOARD_SIZE * MAX_BOARD_SIZE * 2;
+
+/// The key xored into a position's hash while `player` has a piece on the square.
+/// Keys are derived from the square's coordinates instead of a random table, so every
+/// peer computes the same hash for the same position without sharing any state.
+pub fn piece_key(square: Square, player: Player) -> u64 {
+    key_for_index(square.index(MAX_BOARD_SIZE) * 2 + player.index())
+}
+
+/// The key xored into a position's hash while white is the side to move.
+pub fn side_to_move_key() -> u64 {
+    key_for_index(SIDE_TO_MOVE_INDEX)
+}
+
+// SplitMix64 output function, which maps each index to a well distributed 64-bit key.
+fn key_for_index(index: usize) -> u64 {
+    let mut key = ZOBRIST_SEED.wrapping_add((index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    key = (key ^ (key >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    key = (key ^ (key >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    key ^ (key >> 31)
+}
@@ -2,18 +2,46 @@ use core::f32;
 
 use eframe::egui::{self, Color32, Layout, Ui, Vec2};
 
-use crate::game_controller::GameController;
+use crate::engine::BookMove;
+use crate::game_controller::{GameController, Hint};
+use crate::game_logic::analysis::PositionAnalysis;
+use crate::game_logic::{Player, Square};
 
 static BORDER_COLOR: Color32 = Color32::from_rgb(0x54, 0x77, 0x35);
 static BOARD_COLOR: Color32 = Color32::from_rgb(0x26, 0x70, 0x39);
+static BLOCKED_COLOR: Color32 = Color32::from_rgb(0x3A, 0x3A, 0x3A);
 static BUTTON_COLOR: Color32 = Color32::from_rgb(0xFF, 0x5A, 0x36);
 static BLACK_PIECE: egui::ImageSource = egui::include_image!("../../assets/black_piece.png");
 static WHITE_PIECE: egui::ImageSource = egui::include_image!("../../assets/white_piece.png");
+static PREVIEW_TINT: Color32 = Color32::from_rgba_premultiplied(110, 110, 110, 110);
+static LEGAL_MOVE_COLOR: Color32 = Color32::from_rgb(0xF2, 0xE8, 0x5C);
+static FRONTIER_COLOR: Color32 = Color32::from_rgb(0xFF, 0x8C, 0x1A);
+static STABLE_COLOR: Color32 = Color32::from_rgb(0x4F, 0xC3, 0xF7);
+static BOOK_MOVE_COLOR: Color32 = Color32::from_rgb(0xFF, 0xF5, 0x9D);
+static HINT_COLOR: Color32 = Color32::from_rgb(0xE0, 0x40, 0xFB);
+
+/// Squares marked on the board by the analysis overlays picked in the menu.
+#[derive(Default)]
+struct Overlays {
+    legal_moves: Vec<Square>,
+    frontier_discs: Vec<Square>,
+    stable_discs: Vec<Square>,
+    /// Known moves for the side to move, with the scores the opening book has for them.
+    book_moves: Vec<BookMove>,
+    /// The move suggested by the last hint, while it still applies.
+    hint: Option<Hint>
+}
 
 pub struct BoardView {
     chatbox_text: String,
     text_font: egui::FontId,
     rank_font: egui::FontId,
+    /// Pieces that the move under the mouse would flip, found while drawing the last frame.
+    previewed_flips: Vec<Square>,
+    show_legal_moves: bool,
+    show_frontier_discs: bool,
+    show_stable_discs: bool,
+    show_hint_score: bool,
 }
 
 impl BoardView {
@@ -22,35 +50,60 @@ impl BoardView {
             chatbox_text: String::new(),
             text_font: egui::FontId::proportional(16.0),
             rank_font: egui::FontId::monospace(18.0),
+            previewed_flips: Vec::new(),
+            show_legal_moves: false,
+            show_frontier_discs: false,
+            show_stable_discs: false,
+            show_hint_score: false,
          }
     }
 
     pub fn draw(&mut self, ctx: &egui::Context, controller: &mut GameController) {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical_centered(|ui| {
-                let turn_text = match controller.player_turn {
-                    true => "Your turn!",
-                    false => "Waiting for opponent..."
+                let turn_text = match (controller.player_turn, controller.board.in_opening_phase()) {
+                    (true, true) => "Your turn! Place a piece on one of the centre squares.",
+                    (true, false) => "Your turn!",
+                    (false, _) if controller.is_against_computer() => "The computer is thinking...",
+                    (false, _) => "Waiting for opponent..."
                 };
 
+                let analysis = self.analyse(controller);
+                let overlays = self.overlays(controller, analysis.as_ref());
+
                 ui.heading(turn_text);
+                if controller.is_showing_book_moves() {
+                    let book_text = match overlays.book_moves.is_empty() {
+                        false => "In book",
+                        true => "Out of book"
+                    };
+                    ui.label(egui::RichText::new(book_text).font(self.text_font.clone()));
+                }
                 ui.add_space(50.0);
 
                 ui.horizontal_top(|ui| {
                     ui.set_min_width(ui.available_width());
-                    ui.add_space((ui.available_width() / 2.0) - ((8.0*48.0)/2.0) - 45.0);
-                    self.board_widget(ui, controller);
+                    let board_width = controller.board.size() as f32 * 48.0;
+                    ui.add_space((ui.available_width() / 2.0) - (board_width/2.0) - 45.0);
+                    self.board_widget(ui, &overlays, controller);
 
                     ui.vertical_centered(|ui| {
-                        let (yours, opponents) = match controller.is_host {
-                            true => (BLACK_PIECE.clone(), WHITE_PIECE.clone()),
-                            false => (WHITE_PIECE.clone(), BLACK_PIECE.clone()),
+                        let (yours, opponents) = match controller.player_color() {
+                            Player::Black => (BLACK_PIECE.clone(), WHITE_PIECE.clone()),
+                            Player::White => (WHITE_PIECE.clone(), BLACK_PIECE.clone()),
                         };
 
                         ui.heading("yours");
                         ui.add_sized([80.0, 80.0], egui::Image::new(yours));
+                        if let Some(analysis) = &analysis {
+                            self.analysis_widget(ui, &analysis[controller.player_color().index()]);
+                        }
+
                         ui.heading("opponent");
                         ui.add_sized([80.0, 80.0], egui::Image::new(opponents));
+                        if let Some(analysis) = &analysis {
+                            self.analysis_widget(ui, &analysis[controller.player_color().opponent().index()]);
+                        }
                     });
                 });
 
@@ -63,7 +116,56 @@ impl BoardView {
         });
     }
 
-    fn board_widget(&mut self, ui: &mut Ui, controller: &mut GameController) {
+    /// Analyses both sides of the board, as long as any of the overlays is shown.
+    fn analyse(&self, controller: &GameController) -> Option<[PositionAnalysis; 2]> {
+        if !(self.show_legal_moves || self.show_frontier_discs || self.show_stable_discs) {
+            return None;
+        }
+        Some(Player::ALL.map(|player| PositionAnalysis::new(&controller.board, player)))
+    }
+
+    fn overlays(&self, controller: &GameController, analysis: Option<&[PositionAnalysis; 2]>) -> Overlays {
+        let mut overlays = Overlays {
+            book_moves: controller.book_moves(),
+            hint: controller.hint().copied(),
+            ..Overlays::default()
+        };
+
+        let Some(analysis) = analysis else {
+            return overlays;
+        };
+
+        if self.show_legal_moves {
+            overlays.legal_moves = controller.board.legal_moves(controller.player_color());
+        }
+        for side in analysis {
+            if self.show_frontier_discs {
+                overlays.frontier_discs.extend_from_slice(&side.frontier_discs);
+            }
+            if self.show_stable_discs {
+                overlays.stable_discs.extend_from_slice(&side.stable_discs);
+            }
+        }
+        overlays
+    }
+
+    fn analysis_widget(&self, ui: &mut Ui, analysis: &PositionAnalysis) {
+        let lines = [
+            format!("mobility: {}", analysis.mobility),
+            format!("potential: {}", analysis.potential_mobility),
+            format!("frontier: {}", analysis.frontier_discs.len()),
+            format!("stable: {}", analysis.stable_discs.len()),
+        ];
+
+        for line in lines {
+            ui.label(egui::RichText::new(line).font(self.text_font.clone()));
+        }
+        ui.add_space(10.0);
+    }
+
+    fn board_widget(&mut self, ui: &mut Ui, overlays: &Overlays, controller: &mut GameController) {
+        let size = controller.board.size();
+
         egui::Frame::none()
             .fill(BOARD_COLOR)
             .rounding(5.0)
@@ -74,8 +176,8 @@ impl BoardView {
                     ui.horizontal(|ui| {
                         ui.add_space(40.0);
 
-                        for i in 0..8 {
-                            let character = (('A' as u8) + i) as char;
+                        for i in 0..size {
+                            let character = (b'A' + i as u8) as char;
                             let text = egui::RichText::new(character)
                                 .font(self.rank_font.clone())
                                 .color(Color32::WHITE);
@@ -90,7 +192,7 @@ impl BoardView {
                     ui.horizontal_top(|ui| {
                         ui.add_space(8.0);
                         ui.vertical(|ui| {
-                            for i in 0..8 {
+                            for i in 1..=size {
                                 let text = egui::RichText::new(i.to_string())
                                     .font(self.rank_font.clone())
                                     .color(Color32::WHITE);
@@ -107,13 +209,22 @@ impl BoardView {
                             .min_col_width(48.0)
                             .min_row_height(48.0)
                             .spacing(Vec2::new(0.0, 1.5))
-                            .with_row_color(|_, _| Some(BOARD_COLOR.clone()))
+                            .with_row_color(|_, _| Some(BOARD_COLOR))
                             .show(ui, |ui| {
-                                for i in 0..8 {
-                                    for j in 0..8 {
-                                        self.cell_widget(ui, i, j, controller);
+                                let previewed_flips = std::mem::take(&mut self.previewed_flips);
+                                let squares: Vec<Square> = controller.board.squares().collect();
+                                for square in squares {
+                                    let previewed = previewed_flips.contains(&square);
+                                    self.cell_widget(ui, square, previewed, overlays, controller);
+                                    if square.file() == size - 1 {
+                                        ui.end_row();
                                     }
-                                    ui.end_row();
+                                }
+
+                                // The preview is drawn a frame late, so draw another one
+                                // whenever it changes.
+                                if self.previewed_flips != previewed_flips {
+                                    ui.ctx().request_repaint();
                                 }
                             });
                     });
@@ -121,8 +232,20 @@ impl BoardView {
             });
     }
 
-    fn cell_widget(&mut self, ui: &mut Ui, i: usize, j: usize, controller: &mut GameController) {
-        egui::Frame::none()
+    fn cell_widget(&mut self, ui: &mut Ui, square: Square, previewed: bool, overlays: &Overlays,
+        controller: &mut GameController) {
+        if controller.board.is_blocked(square) {
+            egui::Frame::none()
+                .fill(BLOCKED_COLOR)
+                .stroke(egui::Stroke::new(1.0, Color32::BLACK))
+                .show(ui, |ui| {
+                    ui.set_min_height(ui.available_height());
+                    ui.set_min_width(ui.available_width());
+                });
+            return;
+        }
+
+        let cell = egui::Frame::none()
             .inner_margin(0.0)
             .outer_margin(0.0)
             .stroke(egui::Stroke::new(1.0, Color32::BLACK))
@@ -131,25 +254,71 @@ impl BoardView {
                 ui.set_min_width(ui.available_width());
                 ui.horizontal_centered(|ui| {
                     ui.vertical_centered_justified(|ui| {
-                        if let Some(piece) = controller.get_piece_at(i, j) {
-                            let image = match piece {
-                                0 => BLACK_PIECE.clone(),
-                                1 => WHITE_PIECE.clone(),
-                                _ => panic!("Invalid piece type at position {i}:{j}")
+                        if let Some(player) = controller.get_piece_at(square) {
+                            // A piece about to be flipped is faded to show the preview.
+                            let image = match player {
+                                Player::Black => BLACK_PIECE.clone(),
+                                Player::White => WHITE_PIECE.clone()
                             };
-                            ui.add(egui::Image::new(image).sense(egui::Sense::click()));
+                            let tint = if previewed { PREVIEW_TINT } else { Color32::WHITE };
+                            ui.add(egui::Image::new(image).tint(tint).sense(egui::Sense::click()));
                         } else {
                             let button = ui.add(egui::Button::new("")
                                 .frame(false)
                                 .min_size(ui.available_size()));
 
+                            if button.hovered() && controller.player_turn {
+                                self.preview_move(ui, square, button.rect, controller);
+                            }
+
                             if button.clicked() {
-                                controller.try_set_piece_on_board(i, j, false);
+                                if let Err(error) = controller.try_set_piece_on_board(square, false) {
+                                    controller.push_error_to_chat(error);
+                                }
                             }
                         }
                     })
                 });
             });
+
+        let (centre, painter) = (cell.response.rect.center(), ui.painter());
+        if overlays.legal_moves.contains(&square) {
+            painter.circle_filled(centre, 5.0, LEGAL_MOVE_COLOR);
+        }
+        if overlays.frontier_discs.contains(&square) {
+            painter.circle_stroke(centre, 19.0, egui::Stroke::new(2.5, FRONTIER_COLOR));
+        }
+        if overlays.stable_discs.contains(&square) {
+            painter.circle_stroke(centre, 15.0, egui::Stroke::new(2.5, STABLE_COLOR));
+        }
+        if let Some(book_move) = overlays.book_moves.iter().find(|book_move| book_move.square == square) {
+            let score = format!("{:+.1}", book_move.score);
+            painter.text(centre, egui::Align2::CENTER_CENTER, score, self.text_font.clone(), BOOK_MOVE_COLOR);
+        }
+        if let Some(hint) = overlays.hint.filter(|hint| hint.square == square) {
+            painter.circle_stroke(centre, 21.0, egui::Stroke::new(3.0, HINT_COLOR));
+            if self.show_hint_score {
+                let score = format!("{:+.1}", hint.score);
+                painter.text(centre, egui::Align2::CENTER_CENTER, score, self.text_font.clone(), HINT_COLOR);
+            }
+        }
+    }
+
+    /// Draws a faded piece on the hovered square when it is a legal move, and remembers
+    /// which pieces it would flip so they are faded on the next frame.
+    fn preview_move(&mut self, ui: &mut Ui, square: Square, rect: egui::Rect,
+        controller: &GameController) {
+        let player = controller.player_color();
+        let Ok(flips) = controller.board.flips_for(square, player) else {
+            return;
+        };
+
+        let image = match player {
+            Player::Black => BLACK_PIECE.clone(),
+            Player::White => WHITE_PIECE.clone()
+        };
+        egui::Image::new(image).tint(PREVIEW_TINT).paint_at(ui, rect);
+        self.previewed_flips = flips;
     }
 
     fn chat_widget(&mut self, ui: &mut Ui, controller: &mut GameController) {
@@ -158,7 +327,7 @@ impl BoardView {
                 .inner_margin(10.0)
                 .rounding(5.0)
                 .fill(Color32::BLACK)
-                .stroke(egui::Stroke::new(8.0, BORDER_COLOR.clone()))
+                .stroke(egui::Stroke::new(8.0, BORDER_COLOR))
                 .multiply_with_opacity(0.7)
                 .show(ui,|ui| {
                     let textbox = ui.add(egui::TextEdit::singleline(&mut self.chatbox_text)
@@ -166,15 +335,13 @@ impl BoardView {
                         .hint_text("write your message here!")
                         .desired_width(f32::INFINITY));
 
-                    if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        if !self.chatbox_text.is_empty(){
-                            controller.push_chat_message(self.chatbox_text.clone(), false);
+                    if ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.chatbox_text.is_empty() {
+                        controller.push_chat_message(self.chatbox_text.clone(), false);
 
-                            self.chatbox_text.clear();
-                            ui.memory_mut(|mem| {
-                                mem.request_focus(textbox.id);
-                            });
-                        }
+                        self.chatbox_text.clear();
+                        ui.memory_mut(|mem| {
+                            mem.request_focus(textbox.id);
+                        });
                     }
 
                     egui::ScrollArea::vertical()
@@ -206,7 +373,7 @@ impl BoardView {
         egui::Frame::none()
             .rounding(5.0)
             .inner_margin(5.0)
-            .stroke(egui::Stroke::new(8.0, BORDER_COLOR.clone()))
+            .stroke(egui::Stroke::new(8.0, BORDER_COLOR))
             .fill(Color32::BLACK)
             .multiply_with_opacity(0.7)
             .show(ui, |ui| {
@@ -220,9 +387,34 @@ impl BoardView {
                         controller.surrender();
                     }
 
-                    if self.button_widget(ui, "Pass Turn").clicked() {
-                        controller.try_pass_turn()
+                    let can_ask = controller.setup.allow_hints && !controller.is_finding_hint();
+                    let hint_button = ui.add_enabled_ui(can_ask, |ui| {
+                        match controller.is_finding_hint() {
+                            true => self.button_widget(ui, "Finding a hint..."),
+                            false => self.button_widget(ui, "Hint")
+                        }
+                    });
+                    if hint_button.inner.clicked() {
+                        if let Err(error) = controller.ask_for_hint() {
+                            controller.push_error_to_chat(error);
+                        }
+                    }
+
+                    ui.add_space(5.0);
+                    ui.checkbox(&mut self.show_legal_moves, "Show your legal moves");
+                    ui.checkbox(&mut self.show_frontier_discs, "Show frontier pieces");
+                    ui.checkbox(&mut self.show_stable_discs, "Show stable pieces");
+                    let mut show_book_moves = controller.is_showing_book_moves();
+                    let book_checkbox = ui.add_enabled(
+                        controller.setup.allow_hints,
+                        egui::Checkbox::new(&mut show_book_moves, "Show opening book moves (counts as a hint)")
+                    );
+                    if book_checkbox.changed() {
+                        if let Err(error) = controller.show_book_moves(show_book_moves) {
+                            controller.push_error_to_chat(error);
+                        }
                     }
+                    ui.checkbox(&mut self.show_hint_score, "Show the hint's score");
                 });
             });
     }
@@ -232,8 +424,8 @@ impl BoardView {
             .color(Color32::WHITE)
             .font(self.text_font.clone());
 
-        return ui.add(egui::Button::new(text)
-            .fill(BUTTON_COLOR.clone())
+        ui.add(egui::Button::new(text)
+            .fill(BUTTON_COLOR)
             .rounding(5.0)
             .min_size(Vec2::new(0.0, 50.0)))
     }
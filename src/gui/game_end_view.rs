@@ -1,6 +1,7 @@
 use eframe::egui::{self, Color32, Vec2};
 
 use crate::game_controller::{GameController, GameResult};
+use crate::game_logic::Board;
 
 static BUTTON_COLOR: Color32 = Color32::from_rgb(0xFF, 0x5A, 0x36);
 
@@ -32,6 +33,25 @@ impl GameEndView {
                     .size(24.0)
                 );
 
+                ui.add_space(10.0);
+                let ruleset = controller.setup.ruleset;
+
+                // Games ended early by surrendering have no official score.
+                if controller.board.is_game_over() {
+                    let (black_score, white_score) = ruleset.final_score(&controller.board);
+                    let score_text = format!("Final score: black {black_score}\u{2013}{white_score} white");
+                    ui.label(
+                        egui::RichText::new(score_text)
+                        .font(self.text_font.clone())
+                        .size(20.0)
+                    );
+                }
+
+                ui.label(
+                    egui::RichText::new(format!("{}: {}", ruleset.name(), ruleset.win_condition()))
+                    .font(self.text_font.clone())
+                );
+
                 ui.add_space(20.0);
 
                 let button = ui.add(
@@ -1,10 +1,13 @@
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use eframe::egui;
 
 use super::{board_view::BoardView, main_menu_view::MainMenuView, game_end_view::GameEndView};
 use crate::game_controller::{GameController, GameState};
 
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 
 pub fn build_game_window(controller: Arc<Mutex<GameController>>) -> eframe::Result {
     let options = eframe::NativeOptions {
@@ -34,13 +37,20 @@ struct GuiRunner {
 impl eframe::App for GuiRunner {
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         let mut controller = self.controller.lock().unwrap();
+        // Keep drawing while the computer thinks or a hint is looked for, so that the move
+        // shows up once it's found.
+        let computer_thinking = controller.poll_computer();
+        let finding_hint = controller.poll_hint();
+        if computer_thinking || finding_hint {
+            ctx.request_repaint_after(BACKGROUND_POLL_INTERVAL);
+        }
+
         match controller.state {
             GameState::NoConnection => {
                 self.main_menu_view.draw(ctx, &mut controller);
             },
             GameState::Playing => self.board_view.draw(ctx, &mut controller),
             GameState::GameEnded(player_won) => {
-                let player_won = player_won.clone();
                 self.game_end_view.draw(ctx, &mut controller, player_won)
             }
         };
@@ -60,7 +70,7 @@ impl eframe::App for GuiRunner {
 impl GuiRunner {
     fn new(controller: Arc<Mutex<GameController>>) -> Self {
         GuiRunner {
-            controller: controller,
+            controller,
             error: None,
             board_view: BoardView::new(),
             main_menu_view: MainMenuView::new(),
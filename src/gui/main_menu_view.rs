@@ -1,16 +1,31 @@
 use eframe::egui;
 
-use crate::game_controller::GameController;
+use crate::engine::Difficulty;
+use crate::game_controller::{GameController, GameSetup};
+use crate::game_logic::{BoardLayout, Handicap, OpeningList, Player, Rng, Ruleset};
 
 
+static BOARD_SIZES: [usize; 3] = [6, 8, 10];
+
 pub struct MainMenuView{
     socket_addr: String,
+    setup: GameSetup,
+    layout: BoardLayout,
+    /// Whether to start from a random opening, which only exists for 8x8 games on an open board.
+    random_opening: bool,
+    computer_color: Player,
+    difficulty: Difficulty,
 }
 
 impl MainMenuView {
     pub fn new() -> Self {
         MainMenuView {
             socket_addr: "192.168.56.101".to_string(),
+            setup: GameSetup::default(),
+            layout: BoardLayout::Open,
+            random_opening: false,
+            computer_color: Player::White,
+            difficulty: Difficulty::default(),
         }
     }
 
@@ -25,15 +40,140 @@ impl MainMenuView {
                 ui.heading("Connect to Peer:");
                 ui.add_space(20.0);
                 ui.text_edit_singleline(&mut self.socket_addr);
+                ui.add_space(10.0);
+                self.setup_widget(ui, controller);
 
                 let connect_button = ui.add(
                     egui::Button::new("Connect to Address")
                 );
         
                 if connect_button.clicked() {
+                    if controller.is_host {
+                        controller.setup = self.pick_setup(&controller.openings);
+                    }
                     controller.connect_to(&self.socket_addr);
                 }
+
+                if controller.is_host {
+                    ui.add_space(30.0);
+                    self.computer_widget(ui, controller);
+                }
             })
         });
     }
+
+    fn computer_widget(&mut self, ui: &mut egui::Ui, controller: &mut GameController) {
+        ui.heading("Play vs Computer:");
+        ui.add_space(10.0);
+
+        egui::ComboBox::from_label("Computer plays")
+            .selected_text(self.computer_color.to_string())
+            .show_ui(ui, |ui| {
+                for player in Player::ALL {
+                    ui.selectable_value(&mut self.computer_color, player, player.to_string());
+                }
+            });
+
+        egui::ComboBox::from_label("Level")
+            .selected_text(self.difficulty.name())
+            .show_ui(ui, |ui| {
+                for difficulty in Difficulty::ALL {
+                    ui.selectable_value(&mut self.difficulty, difficulty, difficulty.name());
+                }
+            });
+
+        if ui.button("Play vs Computer").clicked() {
+            let setup = self.pick_setup(&controller.openings);
+            let started = controller.play_against_computer(setup, self.computer_color, self.difficulty);
+            if let Err(error) = started {
+                controller.error_queue.lock().unwrap().push(error.to_string());
+            }
+        }
+    }
+
+    /// The chosen setup, with the blocked squares and the opening drawn at random.
+    fn pick_setup(&mut self, openings: &OpeningList) -> GameSetup {
+        let mut rng = Rng::from_time();
+        let size = self.setup.board_size;
+        self.setup.blocked_squares = self.layout.blocked_squares(size, rng.next_u64());
+        self.setup.opening = match self.random_opening && self.allows_random_opening() {
+            true => openings.pick(&mut rng).to_vec(),
+            false => Vec::new()
+        };
+        self.setup.clone()
+    }
+
+    fn setup_widget(&mut self, ui: &mut egui::Ui, controller: &GameController) {
+        if !controller.is_host {
+            let setup = &controller.setup;
+            ui.label("Game chosen by your opponent:");
+            ui.label(format!("{0}x{0} board, {1}", setup.board_size, setup.ruleset.name()));
+            if setup.free_opening {
+                ui.label("Free opening: the first four pieces are placed by the players.");
+            }
+            if !setup.blocked_squares.is_empty() {
+                ui.label(format!("{} blocked squares", setup.blocked_squares.len()));
+            }
+            if let Some(handicap) = setup.handicap {
+                ui.label(format!("Handicap: {handicap}"));
+            }
+            if !setup.opening.is_empty() {
+                let moves: Vec<String> = setup.opening.iter().map(|square| square.to_string()).collect();
+                ui.label(format!("Random opening: {}", moves.join(" ")));
+            }
+            if !setup.allow_hints {
+                ui.label("Hints are turned off.");
+            }
+            return;
+        }
+
+        egui::ComboBox::from_label("Board size")
+            .selected_text(format!("{0}x{0}", self.setup.board_size))
+            .show_ui(ui, |ui| {
+                for size in BOARD_SIZES {
+                    ui.selectable_value(&mut self.setup.board_size, size, format!("{size}x{size}"));
+                }
+            });
+
+        egui::ComboBox::from_label("Ruleset")
+            .selected_text(self.setup.ruleset.name())
+            .show_ui(ui, |ui| {
+                for ruleset in Ruleset::ALL {
+                    ui.selectable_value(&mut self.setup.ruleset, ruleset, ruleset.name());
+                }
+            });
+
+        egui::ComboBox::from_label("Layout")
+            .selected_text(self.layout.name())
+            .show_ui(ui, |ui| {
+                for layout in BoardLayout::ALL {
+                    ui.selectable_value(&mut self.layout, layout, layout.name());
+                }
+            });
+
+        let handicap_text = |handicap: Option<Handicap>| match handicap {
+            Some(handicap) => handicap.to_string(),
+            None => "No handicap".to_string()
+        };
+        egui::ComboBox::from_label("Handicap")
+            .selected_text(handicap_text(self.setup.handicap))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.setup.handicap, None, handicap_text(None));
+                for handicap in Handicap::all().map(Some) {
+                    ui.selectable_value(&mut self.setup.handicap, handicap, handicap_text(handicap));
+                }
+            });
+
+        ui.checkbox(&mut self.setup.free_opening, "Free opening (classic Reversi)");
+        ui.add_enabled(
+            self.allows_random_opening(),
+            egui::Checkbox::new(&mut self.random_opening, "Random balanced opening (XOT, open 8x8 board without handicap only)")
+        );
+        ui.checkbox(&mut self.setup.allow_hints, "Allow hints (your opponent sees when one is taken)");
+    }
+
+    fn allows_random_opening(&self) -> bool {
+        self.setup.board_size == 8 && !self.setup.free_opening && self.setup.handicap.is_none()
+            && self.layout.allows_random_opening()
+    }
 }
@@ -2,4 +2,4 @@ mod rpc_client;
 mod rpc_server;
 
 pub use rpc_client::RpcClient;
-pub use rpc_server::start_rpc_server;
+pub use rpc_server::{serve_rpc, start_rpc_server, RPC_PORT};
@@ -1,3 +1,4 @@
+use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 
 use tonic::transport::Channel;
@@ -5,8 +6,11 @@ use tonic::transport::Channel;
 use crate::othello_rpc::chat_client::ChatClient;
 use crate::othello_rpc::board_client::BoardClient;
 use crate::othello_rpc::game_flow_client::GameFlowClient;
-use crate::othello_rpc::{ChatRequest, Empty, EndRequest, PieceRequest};
+use crate::othello_rpc::{self, BoardSquare, ChatRequest, Empty, EndRequest, PieceRequest, SetupRequest};
+use crate::game_controller::GameSetup;
+use crate::game_logic::{Player, Ruleset, Square};
 use crate::RpcResult;
+use super::RPC_PORT;
 
 pub struct RpcClient{
     chat_client: ChatClient<Channel>,
@@ -17,10 +21,16 @@ pub struct RpcClient{
 }
 
 impl RpcClient {
+    /// Connects to the peer at the given address, on `RPC_PORT` unless the address
+    /// comes with a port of its own such as `127.0.0.1:11070`.
     pub fn new(ip_addr: &str, error_queue: Arc<Mutex<Vec<String>>>) -> Result<Self, tonic::transport::Error> {
-        let chat_url = format!("http://{}:11069", ip_addr);
-        let board_url = format!("http://{}:11069", ip_addr);
-        let game_url = format!("http://{}:11069", ip_addr);
+        let peer_addr = match ip_addr.parse::<SocketAddr>() {
+            Ok(addr) => addr.to_string(),
+            Err(_) => format!("{}:{}", ip_addr, RPC_PORT)
+        };
+        let chat_url = format!("http://{}", peer_addr);
+        let board_url = format!("http://{}", peer_addr);
+        let game_url = format!("http://{}", peer_addr);
 
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -37,16 +47,16 @@ impl RpcClient {
             })?;
 
         Ok(RpcClient{
-            chat_client: chat_client,
-            board_client: board_client,
-            game_flow_client: game_flow_client,
-            error_queue: error_queue,
-            runtime: runtime
+            chat_client,
+            board_client,
+            game_flow_client,
+            error_queue,
+            runtime
         })
     }
 
     pub fn send_chat_message(&mut self, msg: String) {
-        let request = ChatRequest { msg: msg };
+        let request = ChatRequest { msg };
         let mut client = self.chat_client.clone();
         let error_queue = self.error_queue.clone();
 
@@ -56,8 +66,12 @@ impl RpcClient {
         });
     }
 
-    pub fn set_piece(&mut self, rank: usize, file: usize) {
-        let request = PieceRequest { rank: rank as i32, file: file as i32 };
+    pub fn set_piece(&mut self, square: Square, board_hash: u64) {
+        let request = PieceRequest {
+            rank: square.rank() as i32,
+            file: square.file() as i32,
+            board_hash
+        };
         let mut client = self.board_client.clone();
         let error_queue = self.error_queue.clone();
 
@@ -78,30 +92,30 @@ impl RpcClient {
         });
     }
 
-    pub fn change_turn(&mut self) {
+    pub fn undo_move(&mut self) {
         let request = Empty { };
         let mut client = self.game_flow_client.clone();
         let error_queue = self.error_queue.clone();
 
         self.runtime.spawn(async move {
-            let result = client.change_turn(request).await;
+            let result = client.undo_move(request).await;
             handle_error(result, error_queue);
         });
     }
-
-    pub fn undo_move(&mut self) {
+    
+    pub fn announce_hint(&mut self) {
         let request = Empty { };
         let mut client = self.game_flow_client.clone();
         let error_queue = self.error_queue.clone();
 
         self.runtime.spawn(async move {
-            let result = client.undo_move(request).await;
+            let result = client.announce_hint(request).await;
             handle_error(result, error_queue);
         });
     }
-    
-    pub fn connect_to(&mut self) {
-        let request = Empty { };
+
+    pub fn connect_to(&mut self, setup: &GameSetup) {
+        let request = SetupRequest::from(setup);
         let mut client = self.game_flow_client.clone();
         let error_queue = self.error_queue.clone();
 
@@ -112,6 +126,35 @@ impl RpcClient {
     }
 }
 
+impl From<&GameSetup> for SetupRequest {
+    fn from(setup: &GameSetup) -> Self {
+        let ruleset = match setup.ruleset {
+            Ruleset::Standard => othello_rpc::Ruleset::Standard,
+            Ruleset::AntiOthello => othello_rpc::Ruleset::AntiOthello
+        };
+
+        SetupRequest {
+            board_size: setup.board_size as i32,
+            ruleset: ruleset.into(),
+            free_opening: setup.free_opening,
+            blocked_squares: setup.blocked_squares.iter()
+                .map(|square| BoardSquare { rank: square.rank() as i32, file: square.file() as i32 })
+                .collect(),
+            handicap: setup.handicap.map(|handicap| {
+                let player = match handicap.player() {
+                    Player::Black => othello_rpc::PlayerColor::Black,
+                    Player::White => othello_rpc::PlayerColor::White
+                };
+                othello_rpc::Handicap { player: player.into(), corners: handicap.corners() as i32 }
+            }),
+            opening: setup.opening.iter()
+                .map(|square| BoardSquare { rank: square.rank() as i32, file: square.file() as i32 })
+                .collect(),
+            allow_hints: setup.allow_hints
+        }
+    }
+}
+
 fn handle_error(result: RpcResult, error_queue: Arc<Mutex<Vec<String>>>) {
     if let Err(status) = result {
         error_queue.lock().expect("Cannot obtain Mutex resource.").push(
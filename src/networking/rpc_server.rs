@@ -1,13 +1,16 @@
 use std::sync::{Arc, Mutex};
 
+use tokio::net::TcpListener;
+use tonic::transport::server::TcpIncoming;
 use tonic::{Request, Response, transport::Server};
 
-use crate::game_controller::{GameController, GameState};
+use crate::game_controller::{GameController, GameResult, GameSetup, GameState};
 use crate::RpcResult;
 use crate::othello_rpc::chat_server::{ChatServer, Chat};
 use crate::othello_rpc::game_flow_server::{GameFlowServer, GameFlow};
 use crate::othello_rpc::board_server::{BoardServer, Board};
-use crate::othello_rpc::{ChatRequest, Empty, EndRequest, PieceRequest};
+use crate::othello_rpc::{self, ChatRequest, Empty, EndRequest, PieceRequest, SetupRequest};
+use crate::game_logic::{Handicap, Player, Ruleset, Square};
 
 #[derive(Clone)]
 struct RpcServer {
@@ -23,11 +26,24 @@ impl RpcServer {
         Response::new(Empty { })
     }
 
-    fn lock_controller(&self) -> Result<std::sync::MutexGuard<GameController>, tonic::Status> {
+    #[allow(clippy::result_large_err)]
+    fn lock_controller(&self) -> Result<std::sync::MutexGuard<'_, GameController>, tonic::Status> {
         self.controller.lock().map_err(|_| {
             tonic::Status::new(tonic::Code::Internal, "Error while locking Mutex")
         })
     }
+
+    /// Locks the controller for a request from a peer, which is turned away while the
+    /// player is busy with a game against the computer.
+    #[allow(clippy::result_large_err)]
+    fn lock_for_peer(&self) -> Result<std::sync::MutexGuard<'_, GameController>, tonic::Status> {
+        let controller = self.lock_controller()?;
+        if controller.is_against_computer() {
+            return Err(tonic::Status::new(tonic::Code::Unavailable,
+                "Your opponent is playing against the computer."))
+        }
+        Ok(controller)
+    }
 }
 
 #[tonic::async_trait]
@@ -35,7 +51,7 @@ impl Chat for RpcServer {
     async fn send_message(&self, request: Request<ChatRequest>) -> RpcResult {
         let text_msg = request.into_inner().msg;
 
-        let mut controller = self.lock_controller()?;
+        let mut controller = self.lock_for_peer()?;
         controller.push_chat_message(text_msg, true);
 
         Ok(self.build_response())
@@ -46,10 +62,22 @@ impl Chat for RpcServer {
 impl Board for RpcServer {
     async fn set_piece(&self, request: Request<PieceRequest>) -> RpcResult {
         let pieces = request.into_inner();
-        let (rank, file) = (pieces.rank as usize, pieces.file as usize);
-
-        let mut controller = self.lock_controller()?;
-        controller.try_set_piece_on_board(rank, file, true);
+        let mut controller = self.lock_for_peer()?;
+
+        let square = square_from_request(pieces.rank, pieces.file)
+            .filter(|square| controller.board.contains(*square))
+            .ok_or_else(|| tonic::Status::new(tonic::Code::InvalidArgument,
+                "Piece position is outside of the game board."))?;
+        controller.try_set_piece_on_board(square, true).map_err(|error| {
+            controller.push_error_to_chat(&format!("Opponent sent an invalid move. {}", error));
+            tonic::Status::new(tonic::Code::InvalidArgument, error)
+        })?;
+
+        if controller.board.zobrist_hash() != pieces.board_hash {
+            controller.push_error_to_chat("Your board is out of sync with your opponent's.");
+            return Err(tonic::Status::new(tonic::Code::DataLoss,
+                "Boards are out of sync after the last move."))
+        }
 
         Ok(self.build_response())
     }
@@ -57,28 +85,24 @@ impl Board for RpcServer {
 
 #[tonic::async_trait]
 impl GameFlow for RpcServer {
-    async fn end_game(&self, _request: Request<EndRequest>) -> RpcResult {
-        let mut controller = self.lock_controller()?;
-        let player_won = controller.check_if_player_won();
-        controller.state = GameState::GameEnded(player_won);
-
-        Ok(self.build_response())
-    }
+    async fn end_game(&self, request: Request<EndRequest>) -> RpcResult {
+        let player_won = match request.into_inner().game_won {
+            true => GameResult::PlayerWon,
+            false => GameResult::PlayerLost
+        };
 
-    async fn change_turn(&self, _request: Request<Empty>) -> RpcResult {
-        let mut controller = self.lock_controller()?;
-        controller.opponent_passed = true;
-        controller.player_turn = !controller.player_turn;
-        controller.push_warning_to_chat("Opponent forfeited their turn.");
+        let mut controller = self.lock_for_peer()?;
+        controller.state = GameState::GameEnded(player_won);
 
         Ok(self.build_response())
     }
 
     async fn undo_move(&self, _request: Request<Empty>) -> RpcResult {
-        let mut controller = self.lock_controller()?;
+        let mut controller = self.lock_for_peer()?;
 
-        controller.board.revert_to_last_state();
-        controller.player_turn = !controller.player_turn;
+        controller.undo_board_move().map_err(|error| {
+            tonic::Status::new(tonic::Code::FailedPrecondition, error)
+        })?;
         controller.push_warning_to_chat("The last move was undone by the opponent.");
 
         Ok(self.build_response())
@@ -88,26 +112,110 @@ impl GameFlow for RpcServer {
         Ok(self.build_response())
     }
 
-    async fn connect_to(&self, _request: Request<Empty>) -> RpcResult {
-        let mut controller = self.lock_controller()?;
-        
+    async fn connect_to(&self, request: Request<SetupRequest>) -> RpcResult {
+        let setup = GameSetup::try_from(request.into_inner()).map_err(|error| {
+            tonic::Status::new(tonic::Code::InvalidArgument, error)
+        })?;
+
+        let mut controller = self.lock_for_peer()?;
+        controller.apply_setup(setup).map_err(|error| {
+            tonic::Status::new(tonic::Code::InvalidArgument, error)
+        })?;
+
         controller.is_host = false;
-        controller.player_turn = false;
+        controller.player_turn = controller.board.side_to_move() == controller.player_color();
+
+        Ok(self.build_response())
+    }
+
+    async fn announce_hint(&self, _request: Request<Empty>) -> RpcResult {
+        let mut controller = self.lock_for_peer()?;
+
+        let warning = match controller.setup.allow_hints {
+            true => "Your opponent took a hint.",
+            false => "Your opponent took a hint, even though hints are turned off for this game."
+        };
+        controller.push_warning_to_chat(warning);
 
         Ok(self.build_response())
     }
 }
 
+impl TryFrom<SetupRequest> for GameSetup {
+    type Error = &'static str;
+
+    fn try_from(request: SetupRequest) -> Result<Self, Self::Error> {
+        let board_size = usize::try_from(request.board_size)
+            .map_err(|_| "Invalid board size received.")?;
+
+        let ruleset = match othello_rpc::Ruleset::try_from(request.ruleset) {
+            Ok(othello_rpc::Ruleset::Standard) => Ruleset::Standard,
+            Ok(othello_rpc::Ruleset::AntiOthello) => Ruleset::AntiOthello,
+            Err(_) => return Err("Unknown ruleset received.")
+        };
+
+        let blocked_squares = request.blocked_squares.iter()
+            .map(|square| square_from_request(square.rank, square.file))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("Invalid blocked square received.")?;
+
+        let handicap = request.handicap.map(handicap_from_request).transpose()?;
+
+        let opening = request.opening.iter()
+            .map(|square| square_from_request(square.rank, square.file))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("Invalid opening move received.")?;
+
+        Ok(GameSetup {
+            board_size,
+            ruleset,
+            free_opening: request.free_opening,
+            blocked_squares,
+            handicap,
+            opening,
+            allow_hints: request.allow_hints
+        })
+    }
+}
+
+fn handicap_from_request(handicap: othello_rpc::Handicap) -> Result<Handicap, &'static str> {
+    let player = match othello_rpc::PlayerColor::try_from(handicap.player) {
+        Ok(othello_rpc::PlayerColor::Black) => Player::Black,
+        Ok(othello_rpc::PlayerColor::White) => Player::White,
+        Err(_) => return Err("Unknown handicap player received.")
+    };
+
+    let corners = usize::try_from(handicap.corners)
+        .map_err(|_| "Invalid handicap received.")?;
+    Handicap::new(player, corners)
+}
+
+fn square_from_request(rank: i32, file: i32) -> Option<Square> {
+    let (rank, file) = (usize::try_from(rank).ok()?, usize::try_from(file).ok()?);
+    Square::new(rank, file).ok()
+}
+
+/// The port peers reach each other on, unless another one is given.
+pub const RPC_PORT: u16 = 11069;
+
 pub async fn start_rpc_server(game_controller: Arc<Mutex<GameController>>)
     -> Result<(), Box<dyn std::error::Error>> {
-    let addr = format!("0.0.0.0:11069").parse()?;
+    let listener = TcpListener::bind(("0.0.0.0", RPC_PORT)).await?;
+    serve_rpc(game_controller, listener).await
+}
+
+/// Answers peers on a listener that is already bound, such as one on a port picked by the
+/// system, until the server fails.
+pub async fn serve_rpc(game_controller: Arc<Mutex<GameController>>, listener: TcpListener)
+    -> Result<(), Box<dyn std::error::Error>> {
+    let incoming = TcpIncoming::from_listener(listener, false, None).map_err(|error| error as Box<dyn std::error::Error>)?;
     let server = RpcServer::new(game_controller);
 
     Server::builder()
         .add_service(ChatServer::new(server.clone()))
         .add_service(BoardServer::new(server.clone()))
         .add_service(GameFlowServer::new(server))
-        .serve(addr)
+        .serve_with_incoming(incoming)
         .await?;
 
     Ok(())
@@ -0,0 +1,53 @@
+use othello_rs::game_logic::analysis::{self, PositionAnalysis};
+use othello_rs::game_logic::{OthelloBoard, Player};
+
+mod common;
+
+use common::squares;
+
+#[test]
+fn starting_position_features() {
+    let board = OthelloBoard::new();
+    let analysis = PositionAnalysis::new(&board, Player::Black);
+
+    assert_eq!(analysis.mobility, 4);
+    assert_eq!(analysis.potential_mobility, 10);
+    assert_eq!(analysis.frontier_discs, squares(&["e4", "d5"]));
+    assert!(analysis.stable_discs.is_empty());
+}
+
+#[test]
+fn edge_pieces_anchored_to_a_corner_are_stable() {
+    // Black's run along the first rank is anchored to a1, but the piece on e1 next to
+    // white's piece is not, and neither is anything off the edge.
+    let board: OthelloBoard = "\
+        XXXOX---
+        XX------
+        X-------
+        ---XO---
+        ---OX---
+        --------
+        --------
+        --------
+        O".parse().unwrap();
+
+    assert_eq!(analysis::stable_discs(&board, Player::Black), squares(&["a1", "b1", "c1", "a2", "b2", "a3"]));
+    assert!(analysis::stable_discs(&board, Player::White).is_empty());
+}
+
+#[test]
+fn every_piece_on_a_full_board_is_stable() {
+    let board: OthelloBoard = "XOXO OXOX XXOO OOXX X".parse().unwrap();
+
+    assert_eq!(analysis::stable_discs(&board, Player::Black).len(), 8);
+    assert_eq!(analysis::stable_discs(&board, Player::White).len(), 8);
+    assert!(analysis::frontier_discs(&board, Player::Black).is_empty());
+}
+
+#[test]
+fn blocked_squares_are_neither_frontier_nor_potential_moves() {
+    let board: OthelloBoard = "#X-- #OX- -XO- ---- X".parse().unwrap();
+
+    assert_eq!(analysis::frontier_discs(&board, Player::Black), squares(&["b1", "c2", "b3"]));
+    assert_eq!(analysis::potential_mobility(&board, Player::White), 8);
+}
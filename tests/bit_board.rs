@@ -0,0 +1,118 @@
+use othello_rs::game_logic::analysis::PositionAnalysis;
+use othello_rs::game_logic::{BitBoard, Board, OthelloBoard, Player, Ruleset};
+
+mod common;
+
+use common::random_positions;
+
+fn bit_board(board: &OthelloBoard) -> BitBoard {
+    BitBoard::try_from(board).unwrap()
+}
+
+#[test]
+fn starts_from_the_standard_position() {
+    assert_eq!(BitBoard::new(), bit_board(&OthelloBoard::new()));
+}
+
+#[test]
+fn legal_moves_match_the_othello_board() {
+    for board in (0..10).flat_map(random_positions) {
+        let bits = bit_board(&board);
+        for player in Player::ALL {
+            assert_eq!(bits.legal_moves(player), board.legal_moves(player), "{board}");
+            assert_eq!(bits.count_legal_moves(player), board.legal_moves(player).len(), "{board}");
+            assert_eq!(bits.must_pass(player), board.must_pass(player), "{board}");
+        }
+        assert_eq!(bits.is_game_over(), board.is_game_over(), "{board}");
+    }
+}
+
+#[test]
+fn flips_match_the_othello_board() {
+    for board in (10..20).flat_map(random_positions) {
+        let bits = bit_board(&board);
+        for square in board.squares() {
+            for player in Player::ALL {
+                let expected = board.flips_for(square, player);
+                assert_eq!(bits.flips_for(square, player), expected, "{square} for {player} in {board}");
+            }
+        }
+    }
+}
+
+#[test]
+fn scores_match_the_othello_board() {
+    for board in (20..30).flat_map(random_positions) {
+        let bits = bit_board(&board);
+        assert_eq!(bits.count_pieces(), board.count_pieces(), "{board}");
+        assert_eq!(bits.count_empty_squares(), board.count_empty_squares(), "{board}");
+        assert_eq!(bits.final_score(), board.final_score(), "{board}");
+    }
+}
+
+#[test]
+fn perft_matches_the_othello_board() {
+    assert_eq!(BitBoard::new().perft(6), OthelloBoard::new().perft(6));
+
+    for board in random_positions(30).iter().chain(&random_positions(31)).step_by(4) {
+        assert_eq!(bit_board(board).perft(3), board.perft(3), "{board}");
+    }
+}
+
+#[test]
+fn undo_and_redo_replay_the_same_moves() {
+    let game = random_positions(40).pop().unwrap();
+    let mut bits = BitBoard::new();
+    for played in game.history() {
+        if bits.must_pass(bits.side_to_move()) {
+            bits.pass().unwrap();
+        }
+        bits.set_piece(played.square, played.player).unwrap();
+    }
+    for square in game.squares() {
+        assert_eq!(bits.get_piece_at(square), game.get_piece_at(square));
+    }
+
+    let last = bits.undo().unwrap();
+    assert!(bits.get_piece_at(last.square).is_none());
+    assert_eq!(bits.redo().unwrap(), last);
+    assert_eq!(&last, game.history().last().unwrap());
+}
+
+/// What code written against the `Board` trait sees of a position.
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    hash: u64,
+    moves_played: usize,
+    score: (usize, usize),
+    analysis: Vec<PositionAnalysis>
+}
+
+/// Plays the given moves through the `Board` trait alone, the way code that works on
+/// either board does, and records what the board looks like after each of them.
+fn replay<B: Board>(mut board: B, game: &OthelloBoard) -> Vec<Snapshot> {
+    let mut seen = Vec::new();
+    for played in game.history() {
+        if board.must_pass(board.side_to_move()) {
+            board.pass().unwrap();
+        }
+        board.set_piece(played.square, played.player).unwrap();
+
+        seen.push(Snapshot {
+            hash: board.zobrist_hash(),
+            moves_played: board.moves_played(),
+            score: Ruleset::Standard.final_score(&board),
+            analysis: Player::ALL.iter().map(|player| PositionAnalysis::new(&board, *player)).collect()
+        });
+    }
+    assert!(board.is_game_over());
+    seen
+}
+
+#[test]
+fn either_board_plays_the_same_through_the_board_trait() {
+    for seed in 50..55 {
+        let game = random_positions(seed).pop().unwrap();
+        assert_eq!(replay(BitBoard::new(), &game), replay(OthelloBoard::new(), &game), "seed {seed}");
+    }
+}
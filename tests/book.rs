@@ -0,0 +1,148 @@
+use othello_rs::engine::{AlphaBeta, BookEngine, Engine, OpeningBook, Ply, SearchLimits, BOOK_DEPTH};
+use othello_rs::game_logic::{Board, OthelloBoard, Ruleset, Square, Symmetry};
+
+mod common;
+
+use common::{random_game, square};
+
+fn after_moves(moves: &[&str]) -> OthelloBoard {
+    let mut board = OthelloBoard::new();
+    for name in moves {
+        board.set_piece(square(name), board.side_to_move()).unwrap();
+    }
+    board
+}
+
+#[test]
+fn learnt_moves_score_the_final_margin_of_their_player() {
+    let game = random_game(3);
+    let mut book = OpeningBook::new();
+    book.learn(&game, Ruleset::Standard).unwrap();
+
+    let (black, white) = game.final_score();
+    let black_margin = black as f32 - white as f32;
+
+    let first_move = game.history()[0].square;
+    let book_move = book.lookup(&OthelloBoard::new()).into_iter()
+        .find(|book_move| book_move.square == first_move);
+    assert_eq!(book_move.map(|book_move| (book_move.score, book_move.games)), Some((black_margin, 1)));
+
+    let second = after_moves(&[&first_move.to_string()]);
+    assert_eq!(book.best_move(&second).map(|book_move| book_move.score), Some(-black_margin));
+}
+
+#[test]
+fn learning_only_covers_the_start_of_the_game() {
+    let game = random_game(8);
+    let mut book = OpeningBook::new();
+    book.learn(&game, Ruleset::Standard).unwrap();
+
+    let mut board = OthelloBoard::new();
+    for (ply, played) in game.history().iter().enumerate() {
+        if board.side_to_move() != played.player {
+            board.pass().unwrap();
+        }
+        assert_eq!(book.lookup(&board).is_empty(), ply >= BOOK_DEPTH, "ply {ply}");
+        board.set_piece(played.square, played.player).unwrap();
+    }
+}
+
+#[test]
+fn symmetric_positions_share_their_book_moves() {
+    let game = random_game(6);
+    let mut book = OpeningBook::new();
+    book.learn(&game, Ruleset::Standard).unwrap();
+
+    let mut board = game.clone();
+    while board.history().len() > 3 {
+        board.undo();
+    }
+    let book_moves = book.lookup(&board);
+    assert!(!book_moves.is_empty());
+
+    for symmetry in Symmetry::ALL {
+        let transformed = board.transformed(symmetry);
+        let expected: Vec<(Square, f32)> = book_moves.iter()
+            .map(|book_move| (symmetry.apply(book_move.square, 8), book_move.score))
+            .collect();
+        let mut found: Vec<(Square, f32)> = book.lookup(&transformed).iter()
+            .map(|book_move| (book_move.square, book_move.score))
+            .collect();
+
+        found.sort_by_key(|(square, _)| (square.rank(), square.file()));
+        let mut expected = expected;
+        expected.sort_by_key(|(square, _)| (square.rank(), square.file()));
+        assert_eq!(found, expected, "{symmetry:?}");
+    }
+}
+
+#[test]
+fn equivalent_first_moves_are_learnt_together() {
+    let mut book = OpeningBook::new();
+    for seed in 0..10 {
+        book.learn(&random_game(seed), Ruleset::Standard).unwrap();
+    }
+
+    let first_moves = book.lookup(&OthelloBoard::new());
+    assert_eq!(first_moves.len(), 4);
+    assert!(first_moves.iter().all(|book_move| book_move.games == 10));
+}
+
+#[test]
+fn book_survives_a_round_trip_through_text() {
+    let mut book = OpeningBook::new();
+    for seed in 0..5 {
+        book.learn(&random_game(seed), Ruleset::Standard).unwrap();
+    }
+
+    let reloaded: OpeningBook = book.to_string().parse().unwrap();
+    assert_eq!(reloaded.len(), book.len());
+    assert_eq!(reloaded.to_string(), book.to_string());
+}
+
+#[test]
+fn invalid_books_are_rejected() {
+    let start = OthelloBoard::new().to_string();
+
+    assert!(format!("{start} f5 1.5 3").parse::<OpeningBook>().is_ok());
+    assert!(format!("{start} a1 1.5 3").parse::<OpeningBook>().is_err());
+    assert!(format!("{start} f5 many 3").parse::<OpeningBook>().is_err());
+    assert!(format!("{start} f5 1.5").parse::<OpeningBook>().is_err());
+}
+
+#[test]
+fn unfinished_games_are_not_learnt() {
+    let mut book = OpeningBook::new();
+
+    assert!(book.learn(&after_moves(&["f5"]), Ruleset::Standard).is_err());
+    assert!(book.is_empty());
+}
+
+#[test]
+fn bundled_book_knows_the_first_moves() {
+    let book = OpeningBook::standard();
+
+    assert_eq!(book.lookup(&OthelloBoard::new()).len(), 4);
+    assert!(book.best_move(&after_moves(&["f5"])).is_some());
+}
+
+#[test]
+fn book_engine_plays_book_moves_and_searches_out_of_book() {
+    let book = OpeningBook::standard();
+    let search = AlphaBeta::new(SearchLimits { max_depth: 2, max_nodes: None }, Ruleset::Standard);
+    let mut engine = BookEngine::new(book.clone(), Box::new(search));
+
+    let board = after_moves(&["f5"]);
+    let decision = engine.decide(&board);
+    let book_move = book.best_move(&board).unwrap().square;
+    assert_eq!(decision.best_move, Some(book_move));
+    assert_eq!(decision.principal_variation, vec![Ply::Move(book_move)]);
+
+    let mut board = random_game(1);
+    while board.history().len() > 30 {
+        board.undo();
+    }
+    assert!(book.lookup(&board).is_empty());
+    let decision = engine.decide(&board);
+    assert!(board.legal_moves(board.side_to_move()).contains(&decision.best_move.unwrap()));
+}
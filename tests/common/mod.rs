@@ -0,0 +1,89 @@
+//! Helpers shared by the integration tests, each of which uses only some of them.
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use othello_rs::game_logic::{Board, OthelloBoard, Rng, Square};
+
+pub fn square(name: &str) -> Square {
+    name.parse().unwrap()
+}
+
+pub fn squares(names: &[&str]) -> Vec<Square> {
+    names.iter().map(|name| square(name)).collect()
+}
+
+/// Plays a random legal move for the side to move, or passes when they have none.
+/// Returns whether anything was played, which is only not the case once the game is over.
+pub fn play_random_move(board: &mut OthelloBoard, rng: &mut Rng) -> bool {
+    if board.is_game_over() {
+        return false
+    }
+    if board.pass().is_err() {
+        let legal_moves = board.legal_moves(board.side_to_move());
+        board.set_piece(legal_moves[rng.below(legal_moves.len())], board.side_to_move()).unwrap();
+    }
+    true
+}
+
+/// Plays the given number of random moves, counting passes, or fewer if the game ends first.
+pub fn play_random_moves(board: &mut OthelloBoard, rng: &mut Rng, moves: usize) {
+    for _ in 0..moves {
+        if !play_random_move(board, rng) {
+            break;
+        }
+    }
+}
+
+/// A standard game played out with random moves until it is over.
+pub fn random_game(seed: u64) -> OthelloBoard {
+    let mut rng = Rng::new(seed);
+    let mut board = OthelloBoard::new();
+    while play_random_move(&mut board, &mut rng) {}
+    board
+}
+
+/// Positions reached by random play from the start, one for every number of moves
+/// played, so that every stage of the game is covered including the end.
+pub fn random_positions(seed: u64) -> Vec<OthelloBoard> {
+    let mut rng = Rng::new(seed);
+    let mut board = OthelloBoard::new();
+    let mut positions = vec![board.clone()];
+
+    while play_random_move(&mut board, &mut rng) {
+        positions.push(board.clone());
+    }
+    positions
+}
+
+/// A position reached by random moves from the standard start.
+pub fn random_position(rng: &mut Rng, moves: usize) -> OthelloBoard {
+    random_position_on(OthelloBoard::new(), rng, moves)
+}
+
+/// A position reached by random moves from the given board, for other sizes and layouts.
+pub fn random_position_on(mut board: OthelloBoard, rng: &mut Rng, moves: usize) -> OthelloBoard {
+    play_random_moves(&mut board, rng, moves);
+    board
+}
+
+/// A position reached by random moves from the standard start once no more than the
+/// given number of squares are empty, or sooner if the game ends first.
+pub fn endgame_position(rng: &mut Rng, empty_squares: usize) -> OthelloBoard {
+    let mut board = OthelloBoard::new();
+    while board.count_empty_squares() > empty_squares && play_random_move(&mut board, rng) {}
+    board
+}
+
+/// A directory of its own for each caller, so that tests never touch the real data files.
+pub fn fresh_data_dir() -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let name = format!("othello-rs-test-{}-{}", process::id(), COUNT.fetch_add(1, Ordering::Relaxed));
+    let data_dir = env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&data_dir);
+    data_dir
+}
@@ -0,0 +1,323 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use othello_rs::engine::{Difficulty, OpeningBook};
+use othello_rs::game_controller::{GameController, GameSetup, GameState};
+use othello_rs::game_logic::{Handicap, OpeningList, Player};
+use othello_rs::networking::serve_rpc;
+
+mod common;
+
+use common::{fresh_data_dir, square, squares};
+
+fn wait_for_computer(controller: &mut GameController) {
+    while controller.poll_computer() {
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn computer_game(computer: Player) -> GameController {
+    let mut controller = GameController::with_data_dir(fresh_data_dir());
+    controller.play_against_computer(GameSetup::default(), computer, Difficulty::Medium).unwrap();
+    controller
+}
+
+#[test]
+fn the_computer_moves_first_when_it_plays_black() {
+    let mut controller = computer_game(Player::Black);
+    assert_eq!(controller.player_color(), Player::White);
+    assert!(!controller.player_turn);
+
+    wait_for_computer(&mut controller);
+    assert_eq!(controller.board.history().len(), 1);
+    assert!(controller.player_turn);
+}
+
+#[test]
+fn the_computer_replies_to_the_player_s_moves() {
+    let mut controller = computer_game(Player::White);
+    assert!(controller.player_turn);
+    assert!(!controller.poll_computer());
+
+    controller.try_set_piece_on_board(square("f5"), false).unwrap();
+    wait_for_computer(&mut controller);
+    assert_eq!(controller.board.history().len(), 2);
+    assert!(controller.player_turn);
+}
+
+#[test]
+fn undoing_against_the_computer_takes_back_its_reply_too() {
+    let mut controller = computer_game(Player::White);
+    controller.try_set_piece_on_board(square("f5"), false).unwrap();
+    wait_for_computer(&mut controller);
+
+    controller.undo_last_move();
+    assert_eq!(controller.board.history().len(), 0);
+    assert!(controller.player_turn);
+}
+
+#[test]
+fn undoing_while_the_computer_thinks_drops_its_move() {
+    let mut controller = computer_game(Player::White);
+    controller.try_set_piece_on_board(square("f5"), false).unwrap();
+    assert!(controller.poll_computer());
+
+    controller.undo_last_move();
+    wait_for_computer(&mut controller);
+    assert_eq!(controller.board.history().len(), 0);
+    assert!(controller.player_turn);
+}
+
+#[test]
+fn the_computer_keeps_thinking_while_the_player_has_to_pass() {
+    let mut controller = computer_game(Player::White);
+    // Whatever white plays here, black is left without a move.
+    controller.board = "OOOOOOX--OOOOO-XOXXOOOX-OXOOOX-XOXXOXOXXOXOXOX-XOOOOOOO-OOOOOOOO O".parse().unwrap();
+    controller.player_turn = false;
+
+    // Only poll while asked to, the way the GUI only draws another frame when asked to.
+    while controller.poll_computer() {
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(controller.board.history().len() >= 2);
+    assert!(controller.player_turn || matches!(controller.state, GameState::GameEnded(_)));
+}
+
+#[test]
+fn restarting_hands_the_first_move_back_to_the_host() {
+    let mut controller = computer_game(Player::Black);
+    assert!(!controller.player_turn);
+
+    controller.restart_game();
+    assert!(controller.is_host);
+    assert!(controller.player_turn);
+    assert_eq!(controller.player_color(), Player::Black);
+}
+
+#[test]
+fn restarting_makes_a_former_guest_the_host_again() {
+    let mut controller = GameController::with_data_dir(fresh_data_dir());
+    controller.is_host = false;
+    controller.player_turn = false;
+
+    controller.restart_game();
+    assert!(controller.is_host);
+    assert!(controller.player_turn);
+}
+
+// Waits for the peer to get where it should, giving up after a few seconds.
+fn wait_for_peer(peer: &Mutex<GameController>, arrived: impl Fn(&GameController) -> bool, failure: &str) {
+    for _ in 0..1000 {
+        if arrived(&peer.lock().unwrap()) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("{}", failure);
+}
+
+fn wait_for_moves(peer: &Mutex<GameController>, moves: usize) {
+    wait_for_peer(peer, |peer| peer.board.history().len() >= moves, "The peer never received the moves sent to it.");
+}
+
+#[test]
+fn a_computer_game_after_a_networked_one_stays_off_the_network() {
+    let peer = Arc::new(Mutex::new(GameController::with_data_dir(fresh_data_dir())));
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    // Let the system pick a free port, so the test neither needs nor takes the real one.
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let peer_addr = listener.local_addr().unwrap().to_string();
+    let server_controller = peer.clone();
+    runtime.spawn(async move {
+        serve_rpc(server_controller, listener).await.unwrap();
+    });
+
+    let mut controller = GameController::with_data_dir(fresh_data_dir());
+    for _ in 0..1000 {
+        if matches!(controller.state, GameState::Playing) {
+            break;
+        }
+        controller.connect_to(&peer_addr);
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(matches!(controller.state, GameState::Playing), "Could not connect to the peer.");
+    // Requests are sent independently, so a move could overtake the setup it belongs to.
+    wait_for_peer(&peer, |peer| !peer.is_host, "The peer never received the game setup.");
+    controller.try_set_piece_on_board(square("f5"), false).unwrap();
+    wait_for_moves(&peer, 1);
+
+    controller.restart_game();
+    controller.play_against_computer(GameSetup::default(), Player::White, Difficulty::Easy).unwrap();
+    controller.try_set_piece_on_board(square("d3"), false).unwrap();
+    controller.push_chat_message("good luck".to_string(), false);
+    controller.undo_last_move();
+
+    thread::sleep(Duration::from_millis(200));
+    let peer = peer.lock().unwrap();
+    assert_eq!(peer.board.history().len(), 1);
+    assert_eq!(peer.board.history()[0].square, square("f5"));
+    assert!(peer.get_chat_messages().is_empty());
+}
+
+#[test]
+fn chat_works_without_a_connection() {
+    let mut controller = computer_game(Player::White);
+    controller.push_chat_message("good luck".to_string(), false);
+    controller.surrender();
+
+    assert_eq!(controller.get_chat_messages(), &vec!["player: good luck".to_string()]);
+}
+
+fn wait_for_hint(controller: &mut GameController) {
+    while controller.poll_hint() {
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn hints_suggest_a_legal_move_until_the_board_changes() {
+    let mut controller = computer_game(Player::White);
+    controller.ask_for_hint().unwrap();
+    assert!(controller.is_finding_hint());
+    assert!(controller.ask_for_hint().is_err());
+
+    wait_for_hint(&mut controller);
+    let hint = *controller.hint().unwrap();
+    assert!(controller.board.legal_moves(Player::Black).contains(&hint.square));
+    assert!(controller.get_chat_messages().last().unwrap().starts_with("WARNING: You took a hint"));
+
+    controller.try_set_piece_on_board(hint.square, false).unwrap();
+    assert_eq!(controller.hint(), None);
+    assert!(controller.ask_for_hint().is_err());
+}
+
+#[test]
+fn hints_for_a_position_since_left_are_dropped() {
+    let mut controller = computer_game(Player::White);
+    controller.ask_for_hint().unwrap();
+    controller.try_set_piece_on_board(square("f5"), false).unwrap();
+
+    wait_for_hint(&mut controller);
+    assert_eq!(controller.hint(), None);
+    assert!(controller.get_chat_messages().is_empty());
+}
+
+#[test]
+fn hints_can_be_turned_off() {
+    let mut controller = GameController::with_data_dir(fresh_data_dir());
+    let setup = GameSetup { allow_hints: false, ..GameSetup::default() };
+    controller.play_against_computer(setup, Player::White, Difficulty::Easy).unwrap();
+
+    assert!(controller.ask_for_hint().is_err());
+    assert_eq!(controller.hint(), None);
+}
+
+#[test]
+fn showing_the_book_counts_as_taking_a_hint() {
+    let mut controller = computer_game(Player::White);
+    assert!(controller.book_moves().is_empty());
+
+    controller.show_book_moves(true).unwrap();
+    assert!(!controller.book_moves().is_empty());
+    assert_eq!(controller.get_chat_messages().len(), 1);
+    assert!(controller.get_chat_messages()[0].starts_with("WARNING: You turned on the opening book"));
+
+    controller.restart_game();
+    assert!(!controller.is_showing_book_moves());
+}
+
+#[test]
+fn the_book_is_hidden_when_hints_are_turned_off() {
+    let mut controller = GameController::with_data_dir(fresh_data_dir());
+    let setup = GameSetup { allow_hints: false, ..GameSetup::default() };
+    controller.play_against_computer(setup, Player::White, Difficulty::Easy).unwrap();
+
+    assert!(controller.show_book_moves(true).is_err());
+    assert!(controller.book_moves().is_empty());
+}
+
+// Plays the first legal move every turn until the game is over. The computer may have to
+// move several times in a row when the player has to pass, and has to keep asking to be
+// polled until it's done.
+fn play_to_the_end(controller: &mut GameController) {
+    while !matches!(controller.state, GameState::GameEnded(_)) {
+        if !controller.player_turn {
+            wait_for_computer(controller);
+            assert!(controller.player_turn || matches!(controller.state, GameState::GameEnded(_)));
+            continue;
+        }
+        let square = controller.board.legal_moves(controller.player_color())[0];
+        controller.try_set_piece_on_board(square, false).unwrap();
+    }
+}
+
+#[test]
+fn finished_standard_games_are_added_to_the_book() {
+    let data_dir = fresh_data_dir();
+    let mut controller = GameController::with_data_dir(&data_dir);
+    controller.play_against_computer(GameSetup::default(), Player::White, Difficulty::Easy).unwrap();
+    play_to_the_end(&mut controller);
+
+    assert_ne!(controller.book.to_string(), OpeningBook::standard().to_string());
+    let reloaded = GameController::with_data_dir(&data_dir);
+    assert_eq!(reloaded.book.to_string(), controller.book.to_string());
+    fs::remove_dir_all(data_dir).unwrap();
+}
+
+#[test]
+fn handicap_games_are_not_added_to_the_book() {
+    let data_dir = fresh_data_dir();
+    let mut controller = GameController::with_data_dir(&data_dir);
+    let handicap = Handicap::new(Player::Black, 2).unwrap();
+    let setup = GameSetup { handicap: Some(handicap), ..GameSetup::default() };
+    controller.play_against_computer(setup, Player::White, Difficulty::Easy).unwrap();
+    play_to_the_end(&mut controller);
+
+    assert!(!data_dir.exists());
+}
+
+#[test]
+fn openings_are_read_from_the_data_directory() {
+    let data_dir = fresh_data_dir();
+    assert_eq!(
+        GameController::with_data_dir(&data_dir).openings.openings(),
+        OpeningList::standard().openings()
+    );
+
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::write(data_dir.join("openings.txt"), "f5d6c3\n").unwrap();
+    let controller = GameController::with_data_dir(&data_dir);
+    fs::remove_dir_all(data_dir).unwrap();
+
+    assert_eq!(controller.openings.openings(), &[squares(&["f5", "d6", "c3"])]);
+}
+
+#[test]
+fn the_moves_of_the_starting_opening_cant_be_undone() {
+    let mut controller = GameController::with_data_dir(fresh_data_dir());
+    let setup = GameSetup { opening: squares(&["f5", "d6"]), ..GameSetup::default() };
+    controller.apply_setup(setup).unwrap();
+
+    assert!(controller.undo_board_move().is_err());
+    assert_eq!(controller.board.history().len(), 2);
+
+    controller.board.set_piece(square("c3"), Player::Black).unwrap();
+    assert!(controller.undo_board_move().is_ok());
+    assert!(controller.undo_board_move().is_err());
+    assert_eq!(controller.board.history().len(), 2);
+}
+
+#[test]
+fn a_handicap_game_cannot_start_from_a_random_opening() {
+    let mut controller = GameController::with_data_dir(fresh_data_dir());
+    let setup = GameSetup {
+        handicap: Some(Handicap::new(Player::Black, 2).unwrap()),
+        opening: squares(&["f5", "d6"]),
+        ..GameSetup::default()
+    };
+
+    assert!(controller.apply_setup(setup).is_err());
+    assert_eq!(controller.setup, GameSetup::default());
+}
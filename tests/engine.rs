@@ -0,0 +1,300 @@
+use othello_rs::engine::{
+    self, AlphaBeta, Difficulty, EndgameSolver, Engine, Evaluator, HeuristicEvaluator, MonteCarlo,
+    MonteCarloSettings, OpeningBook, Outcome, Ply, SearchLimits, SolveMode
+};
+use othello_rs::game_logic::{Board, BitBoard, OthelloBoard, Player, Rng, Ruleset};
+
+mod common;
+
+use common::{endgame_position, random_position};
+
+fn limits(max_depth: u32) -> SearchLimits {
+    SearchLimits { max_depth, max_nodes: None }
+}
+
+// Plain negamax without any pruning, which alpha-beta has to agree with.
+fn negamax(board: &mut OthelloBoard, depth: u32, evaluator: &HeuristicEvaluator) -> i32 {
+    let player = board.side_to_move();
+    let moves = board.legal_moves(player);
+    if moves.is_empty() && board.is_game_over() {
+        return engine::terminal_score(board, player, Ruleset::Standard);
+    }
+    if depth == 0 {
+        return evaluator.evaluate(board, player);
+    }
+    if moves.is_empty() {
+        let mut passed = board.clone();
+        passed.pass().unwrap();
+        return -negamax(&mut passed, depth, evaluator);
+    }
+
+    moves.into_iter().map(|square| {
+        board.set_piece(square, player).unwrap();
+        let score = -negamax(board, depth - 1, evaluator);
+        board.undo();
+        score
+    }).max().unwrap()
+}
+
+#[test]
+fn alpha_beta_scores_match_plain_negamax() {
+    let mut rng = Rng::new(7);
+    let evaluator = HeuristicEvaluator::new(Ruleset::Standard);
+
+    for moves in [0, 10, 25, 40, 52] {
+        let mut board = random_position(&mut rng, moves);
+        let result = AlphaBeta::new(limits(3), Ruleset::Standard).search(&board);
+
+        assert_eq!(result.score, negamax(&mut board, result.depth, &evaluator), "{board}");
+    }
+}
+
+#[test]
+fn heuristic_scores_bit_boards_like_othello_boards() {
+    let mut rng = Rng::new(11);
+
+    for moves in [0, 8, 20, 35, 50, 60] {
+        let board = random_position(&mut rng, moves);
+        let bit_board = BitBoard::try_from(&board).unwrap();
+
+        for ruleset in [Ruleset::Standard, Ruleset::AntiOthello] {
+            let evaluator = HeuristicEvaluator::new(ruleset);
+            for player in [Player::Black, Player::White] {
+                assert_eq!(
+                    evaluator.evaluate_bit_board(&bit_board, player),
+                    evaluator.evaluate(&board, player),
+                    "{board}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn principal_variation_is_playable_and_starts_with_the_best_move() {
+    let board = OthelloBoard::new();
+    let result = AlphaBeta::new(limits(5), Ruleset::Standard).search(&board);
+
+    assert_eq!(result.depth, 5);
+    assert_eq!(result.principal_variation.first(), result.best_move.map(Ply::Move).as_ref());
+
+    let mut board = board;
+    for ply in result.principal_variation {
+        match ply {
+            Ply::Move(square) => board.set_piece(square, board.side_to_move()).unwrap(),
+            Ply::Pass => board.pass().unwrap()
+        }
+    }
+}
+
+#[test]
+fn node_budget_stops_the_search_early() {
+    let limits = SearchLimits { max_depth: 30, max_nodes: Some(500) };
+    let result = AlphaBeta::new(limits, Ruleset::Standard).search(&OthelloBoard::new());
+
+    assert!(result.best_move.is_some());
+    assert!((1..30).contains(&result.depth));
+}
+
+#[test]
+fn forced_pass_has_no_best_move() {
+    let board: OthelloBoard = "OOO- XOX- XXOX XOOO X".parse().unwrap();
+    let result = AlphaBeta::new(limits(4), Ruleset::Standard).search(&board);
+
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.principal_variation.first(), Some(&Ply::Pass));
+}
+
+#[test]
+fn finished_games_score_beyond_any_evaluation() {
+    // White has been wiped out and is to move in a game that is already over.
+    let board: OthelloBoard = "XX-- XXXX XXXX X--X O".parse().unwrap();
+    let result = AlphaBeta::new(limits(4), Ruleset::Standard).search(&board);
+
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, -(engine::WIN_SCORE + 16));
+    assert_eq!(engine::final_margin(&board, Player::Black, Ruleset::AntiOthello), -12);
+}
+
+fn play_line(board: &mut OthelloBoard, line: &[Ply]) {
+    for ply in line {
+        match ply {
+            Ply::Move(square) => board.set_piece(*square, board.side_to_move()).unwrap(),
+            Ply::Pass => board.pass().unwrap()
+        }
+    }
+}
+
+#[test]
+fn endgame_solver_agrees_with_a_full_depth_search() {
+    let mut rng = Rng::new(21);
+
+    for ruleset in Ruleset::ALL {
+        for _ in 0..4 {
+            let board = endgame_position(&mut rng, 9);
+            let solved = EndgameSolver::new(ruleset, SolveMode::Exact).solve(&board);
+            let searched = AlphaBeta::new(limits(20), ruleset).search(&board);
+
+            assert_eq!(searched.score, solved.margin + engine::WIN_SCORE * solved.margin.signum(), "{board}");
+        }
+    }
+}
+
+#[test]
+fn endgame_line_reaches_the_solved_margin() {
+    let mut rng = Rng::new(5);
+    let board = endgame_position(&mut rng, 14);
+    let player = board.side_to_move();
+    let result = EndgameSolver::new(Ruleset::Standard, SolveMode::Exact).solve(&board);
+
+    let mut board = board;
+    play_line(&mut board, &result.principal_variation);
+
+    assert!(board.is_game_over());
+    assert_eq!(engine::final_margin(&board, player, Ruleset::Standard), result.margin);
+}
+
+#[test]
+fn win_loss_draw_mode_finds_the_same_outcome() {
+    let mut rng = Rng::new(8);
+
+    for _ in 0..5 {
+        let board = endgame_position(&mut rng, 12);
+        let exact = EndgameSolver::new(Ruleset::Standard, SolveMode::Exact).solve(&board);
+        let outcome = EndgameSolver::new(Ruleset::Standard, SolveMode::WinLossDraw).solve(&board);
+
+        assert_eq!(outcome.outcome(), exact.outcome(), "{board}");
+        assert!(outcome.margin.abs() <= 1);
+    }
+}
+
+#[test]
+fn endgame_solver_handles_boards_with_blocked_squares() {
+    // Blocked boards can't use the bit board, so this goes through the general board.
+    let board: OthelloBoard = "\
+        #XXXXXX#
+        XXXXXXXX
+        XXOOOXXX
+        XXOXOXX-
+        XXOOOXX-
+        XXXXOX--
+        XXXXXO--
+        #XXXXXX#
+        O".parse().unwrap();
+    let result = EndgameSolver::new(Ruleset::Standard, SolveMode::Exact).solve(&board);
+
+    let mut played = board.clone();
+    play_line(&mut played, &result.principal_variation);
+    assert!(played.is_game_over());
+    assert_eq!(engine::final_margin(&played, Player::White, Ruleset::Standard), result.margin);
+}
+
+fn monte_carlo(playouts: u32, seed: u64) -> MonteCarlo {
+    let settings = MonteCarloSettings { playouts, ..MonteCarloSettings::default() };
+    MonteCarlo::new(settings, Ruleset::Standard, Rng::new(seed))
+}
+
+#[test]
+fn monte_carlo_is_reproducible_from_its_seed() {
+    let board = random_position(&mut Rng::new(4), 20);
+
+    let first = monte_carlo(300, 9).search(&board);
+    let second = monte_carlo(300, 9).search(&board);
+
+    assert_eq!(first, second);
+    assert_eq!(first.playouts, 300);
+}
+
+#[test]
+fn monte_carlo_finds_winning_moves_near_the_end() {
+    let mut rng = Rng::new(13);
+
+    for seed in 0..5 {
+        let board = endgame_position(&mut rng, 5);
+        let mut solver = EndgameSolver::new(Ruleset::Standard, SolveMode::WinLossDraw);
+        let best_outcome = solver.solve(&board).outcome();
+        let Some(square) = monte_carlo(3000, seed).search(&board).best_move else {
+            continue;
+        };
+
+        let mut played = board.clone();
+        played.set_piece(square, played.side_to_move()).unwrap();
+        let reply = solver.solve(&played);
+        let outcome = match reply.outcome() {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win
+        };
+        assert_eq!(outcome, best_outcome, "{board}");
+    }
+}
+
+#[test]
+fn monte_carlo_passes_when_it_has_no_moves() {
+    let board: OthelloBoard = "OOO- XOX- XXOX XOOO X".parse().unwrap();
+    let result = monte_carlo(200, 1).search(&board);
+
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.principal_variation.first(), Some(&Ply::Pass));
+}
+
+#[test]
+fn monte_carlo_plays_out_boards_with_blocked_squares() {
+    // Like the solver, this has to play out on the general board.
+    let board: OthelloBoard = "\
+        #XXXXXX#
+        XXXXXXXX
+        XXOOOXXX
+        XXOXOXX-
+        XXOOOXX-
+        XXXXOX--
+        XXXXXO--
+        #XXXXXX#
+        O".parse().unwrap();
+    let result = monte_carlo(300, 5).search(&board);
+
+    let square = result.best_move.unwrap();
+    assert!(board.legal_moves(Player::White).contains(&square));
+    assert_eq!(result.playouts, 300);
+}
+
+#[test]
+fn every_engine_decides_on_a_legal_move() {
+    let board = random_position(&mut Rng::new(17), 44);
+    let mut engines: Vec<Box<dyn Engine>> = vec![
+        Box::new(AlphaBeta::new(limits(3), Ruleset::Standard)),
+        Box::new(EndgameSolver::new(Ruleset::Standard, SolveMode::WinLossDraw)),
+        Box::new(monte_carlo(500, 2))
+    ];
+
+    for engine in &mut engines {
+        let decision = engine.decide(&board);
+        let square = decision.best_move.unwrap();
+
+        assert!(board.legal_moves(board.side_to_move()).contains(&square));
+        assert_eq!(decision.principal_variation.first(), Some(&Ply::Move(square)));
+        assert!((0.0..=1.0).contains(&decision.confidence));
+    }
+}
+
+#[test]
+fn every_difficulty_plays_legal_moves() {
+    let board = random_position(&mut Rng::new(23), 48);
+    let book = OpeningBook::standard();
+
+    for ruleset in Ruleset::ALL {
+        for difficulty in Difficulty::ALL {
+            let square = difficulty.engine(ruleset, &book, None).decide(&board).best_move.unwrap();
+            assert!(board.legal_moves(board.side_to_move()).contains(&square), "{}", difficulty.name());
+        }
+    }
+}
+
+#[test]
+fn confidence_is_certain_once_the_game_is_over() {
+    // White has been wiped out and is to move in a game that is already over.
+    let board: OthelloBoard = "XX-- XXXX XXXX X--X O".parse().unwrap();
+
+    assert_eq!(AlphaBeta::new(limits(2), Ruleset::Standard).decide(&board).confidence, 0.0);
+    assert_eq!(monte_carlo(10, 0).decide(&board).confidence, 0.0);
+}
@@ -0,0 +1,87 @@
+use othello_rs::game_logic::{OthelloBoard, Player, Square};
+
+mod common;
+
+use common::square;
+
+#[test]
+fn starting_moves_are_the_four_standard_openings() {
+    let board = OthelloBoard::new();
+    let expected: Vec<Square> = ["d3", "c4", "f5", "e6"].into_iter().map(square).collect();
+
+    assert_eq!(board.legal_moves(Player::Black), expected);
+}
+
+#[test]
+fn move_flips_along_every_direction() {
+    // Every neighbour of c3 is a white piece backed by a black one, apart from the
+    // white pieces on e4 and d5, which only fill the centre.
+    let mut board: OthelloBoard = "\
+        X-X-X---
+        -OOO----
+        XO-OX---
+        -OOOO---
+        X-XOX---
+        --------
+        --------
+        --------
+        X".parse().unwrap();
+
+    board.set_piece(square("c3"), Player::Black).unwrap();
+
+    assert_eq!(board.history()[0].flipped.len(), 8);
+    assert_eq!(board.count_pieces(), (17, 2));
+}
+
+#[test]
+fn move_stops_flanking_at_gaps_and_board_edges() {
+    let mut board: OthelloBoard = "\
+        -OO-OX--
+        ------OO
+        --------
+        ---XO---
+        ---OX---
+        --------
+        --------
+        --------
+        X".parse().unwrap();
+
+    // The white pieces next to a1 are followed by an empty square, and the ones next
+    // to f2 by the edge of the board.
+    assert!(board.set_piece(square("a1"), Player::Black).is_err());
+    assert!(board.set_piece(square("f2"), Player::Black).is_err());
+
+    board.set_piece(square("d1"), Player::Black).unwrap();
+    assert_eq!(board.history()[0].flipped, vec![square("e1")]);
+    assert_eq!(board.get_piece_at(square("c1")), Some(Player::White));
+}
+
+#[test]
+fn illegal_moves_are_rejected_without_changing_the_board() {
+    let mut board = OthelloBoard::new();
+    let before = board.to_string();
+
+    assert!(board.set_piece(square("a1"), Player::Black).is_err());
+    assert!(board.set_piece(square("d4"), Player::Black).is_err());
+    assert!(board.set_piece(square("d3"), Player::White).is_err());
+
+    assert_eq!(board.to_string(), before);
+    assert!(board.history().is_empty());
+}
+
+#[test]
+fn undo_restores_every_position_along_a_game() {
+    let mut board = OthelloBoard::new();
+    let mut positions = vec![board.to_string()];
+
+    while let Some(next) = board.legal_moves(board.side_to_move()).first().copied() {
+        board.set_piece(next, board.side_to_move()).unwrap();
+        positions.push(board.to_string());
+    }
+
+    positions.pop();
+    while let Some(position) = positions.pop() {
+        board.undo().unwrap();
+        assert_eq!(board.to_string(), position);
+    }
+}
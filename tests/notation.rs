@@ -0,0 +1,76 @@
+use othello_rs::game_logic::{OthelloBoard, Player, Square};
+
+const START: &str = "---------------------------OX------XO--------------------------- X";
+
+#[test]
+fn squares_survive_a_round_trip_through_text() {
+    for name in ["a1", "d3", "h8", "j10", "p16"] {
+        assert_eq!(name.parse::<Square>().unwrap().to_string(), name);
+    }
+    assert_eq!("D3".parse::<Square>(), "d3".parse::<Square>());
+    assert_eq!(" e6 ".parse::<Square>(), "e6".parse::<Square>());
+}
+
+#[test]
+fn squares_are_created_from_rank_then_file() {
+    let square = Square::new(2, 5).unwrap();
+
+    assert_eq!((square.rank(), square.file()), (2, 5));
+    assert_eq!(square, "f3".parse().unwrap());
+}
+
+#[test]
+fn squares_with_a_bad_file_are_rejected() {
+    for name in ["", "3d", "?3", "é3", "q1", "z9"] {
+        assert!(name.parse::<Square>().is_err(), "{name:?}");
+    }
+}
+
+#[test]
+fn squares_with_a_bad_rank_are_rejected() {
+    for name in ["a", "a0", "a-1", "a17", "ab", "a1b", "a 1", "a1.5"] {
+        assert!(name.parse::<Square>().is_err(), "{name:?}");
+    }
+}
+
+#[test]
+fn positions_survive_a_round_trip_through_text() {
+    let board: OthelloBoard = START.parse().unwrap();
+    assert_eq!(board.to_string(), OthelloBoard::new().to_string());
+
+    let blocked: OthelloBoard = "#--- -XO- -OX- ---# O".parse().unwrap();
+    assert_eq!(blocked.size(), 4);
+    assert_eq!(blocked.side_to_move(), Player::White);
+    assert!(blocked.is_blocked("a1".parse().unwrap()));
+    assert_eq!(blocked.to_string().parse::<OthelloBoard>().unwrap().to_string(), blocked.to_string());
+}
+
+#[test]
+fn positions_of_the_wrong_length_are_rejected() {
+    let too_short = START.replacen('-', "", 1);
+    let too_long = START.replacen('-', "--", 1);
+    let odd_size = format!("{} X", "-".repeat(25));
+    let too_big = format!("{} X", "-".repeat(18 * 18));
+
+    for position in ["", "X", too_short.as_str(), too_long.as_str(), odd_size.as_str(), too_big.as_str()] {
+        assert!(position.parse::<OthelloBoard>().is_err(), "{position:?}");
+    }
+}
+
+#[test]
+fn positions_with_a_bad_square_are_rejected() {
+    let position = START.replacen('-', "x", 1);
+    assert!(position.parse::<OthelloBoard>().is_err());
+
+    let position = START.replacen('-', "*", 1);
+    assert!(position.parse::<OthelloBoard>().is_err());
+}
+
+#[test]
+fn positions_with_a_bad_side_to_move_are_rejected() {
+    for side in ["-", "#", "x", "B", "XO"] {
+        let position = START.replace(" X", &format!(" {side}"));
+        assert!(position.parse::<OthelloBoard>().is_err(), "{position:?}");
+    }
+    assert!(START.trim_end_matches(" X").parse::<OthelloBoard>().is_err());
+}
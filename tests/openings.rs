@@ -0,0 +1,74 @@
+use std::fs;
+
+use othello_rs::game_controller::GameSetup;
+use othello_rs::game_logic::{BoardLayout, OpeningList};
+
+mod common;
+
+use common::{fresh_data_dir, squares};
+
+#[test]
+fn openings_are_read_one_per_line() {
+    let text = "# two openings\n\nf5d6c3\n  f5 f6 e6 \n";
+    let openings: OpeningList = text.parse().unwrap();
+
+    assert_eq!(openings.openings(), &[squares(&["f5", "d6", "c3"]), squares(&["f5", "f6", "e6"])]);
+}
+
+#[test]
+fn the_bundled_openings_are_legal() {
+    assert!(!OpeningList::standard().openings().is_empty());
+}
+
+#[test]
+fn random_openings_are_only_played_on_layouts_that_leave_room_for_them() {
+    assert!(!BoardLayout::RandomObstacles.allows_random_opening());
+
+    for layout in BoardLayout::ALL.into_iter().filter(BoardLayout::allows_random_opening) {
+        for seed in 0..20 {
+            for opening in OpeningList::standard().openings() {
+                let setup = GameSetup {
+                    blocked_squares: layout.blocked_squares(8, seed),
+                    opening: opening.clone(),
+                    ..GameSetup::default()
+                };
+                assert!(setup.build_board().is_ok(), "{} with seed {seed}", layout.name());
+            }
+        }
+    }
+}
+
+#[test]
+fn lists_without_openings_are_rejected() {
+    assert!("".parse::<OpeningList>().is_err());
+    assert!("# only a comment\n\n".parse::<OpeningList>().is_err());
+}
+
+#[test]
+fn illegal_openings_are_rejected() {
+    // d6 is taken by then, and a1 never flanks anything at the start.
+    assert!("f5d6d6".parse::<OpeningList>().is_err());
+    assert!("a1".parse::<OpeningList>().is_err());
+    assert!("f5d6c3\na1".parse::<OpeningList>().is_err());
+}
+
+#[test]
+fn badly_written_moves_are_rejected() {
+    assert!("5f".parse::<OpeningList>().is_err());
+    assert!("f5d".parse::<OpeningList>().is_err());
+    assert!("f5z9".parse::<OpeningList>().is_err());
+    assert!("f5-d6".parse::<OpeningList>().is_err());
+}
+
+#[test]
+fn openings_can_be_loaded_from_a_file() {
+    let data_dir = fresh_data_dir();
+    let path = data_dir.join("openings.txt");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::write(&path, "f5d6\n").unwrap();
+    let loaded = OpeningList::load(&path);
+    fs::remove_dir_all(&data_dir).unwrap();
+
+    assert_eq!(loaded.unwrap().openings(), &[squares(&["f5", "d6"])]);
+    assert!(OpeningList::load(&path).is_err());
+}
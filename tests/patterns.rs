@@ -0,0 +1,148 @@
+use std::fs;
+
+use othello_rs::engine::{
+    self, Evaluator, GameRecord, HeuristicEvaluator, PatternEvaluator, PatternWeights, Trainer, TrainingSettings
+};
+use othello_rs::game_logic::{Board, BitBoard, OthelloBoard, Player, Ruleset};
+
+mod common;
+
+use common::{fresh_data_dir, random_game};
+
+fn trained_weights(games: u64, steps: u32) -> (Trainer, PatternWeights) {
+    let mut trainer = Trainer::new(Ruleset::Standard);
+    for seed in 0..games {
+        trainer.add_game(&GameRecord::from_game(&random_game(seed), Ruleset::Standard).unwrap()).unwrap();
+    }
+
+    let mut weights = PatternWeights::new(Ruleset::Standard);
+    for _ in 0..steps {
+        trainer.step(&mut weights, &TrainingSettings::default());
+    }
+    (trainer, weights)
+}
+
+#[test]
+fn game_records_survive_a_round_trip_through_text() {
+    let game = random_game(4);
+    let record = GameRecord::from_game(&game, Ruleset::Standard).unwrap();
+
+    assert_eq!(record.score, game.final_score());
+    assert_eq!(record.to_string().parse::<GameRecord>().unwrap(), record);
+}
+
+#[test]
+fn invalid_game_records_are_rejected() {
+    assert!("f5d6c3 3-1".parse::<GameRecord>().is_ok());
+    assert!("f5f5 3-1".parse::<GameRecord>().is_err());
+    assert!("f5d6c3".parse::<GameRecord>().is_err());
+    assert!("f5d6c3 three-one".parse::<GameRecord>().is_err());
+}
+
+#[test]
+fn training_reduces_the_prediction_error() {
+    let (trainer, weights) = trained_weights(20, 30);
+    let moves: usize = (0..20).map(|seed| random_game(seed).history().len()).sum();
+
+    assert_eq!(trainer.len(), moves);
+    assert!(trainer.error(&weights) < trainer.error(&PatternWeights::new(Ruleset::Standard)) * 0.75);
+}
+
+#[test]
+fn weights_survive_a_round_trip_through_text() {
+    let (_, weights) = trained_weights(5, 5);
+    let reloaded: PatternWeights = weights.to_string().parse().unwrap();
+
+    let mut position = random_game(30);
+    while position.history().len() > 30 {
+        position.undo();
+    }
+    assert_eq!(reloaded.predict(&position, Player::Black), weights.predict(&position, Player::Black));
+    assert_eq!(reloaded, weights);
+}
+
+#[test]
+fn invalid_weights_are_rejected() {
+    assert!("ruleset standard\nedge2x 0 0120012000 1.5".parse::<PatternWeights>().is_ok());
+    assert!("ruleset standard\nbias 5 - 1.5".parse::<PatternWeights>().is_ok());
+    assert!("ruleset standard\nedge2x 6 0120012000 1.5".parse::<PatternWeights>().is_err());
+    assert!("ruleset standard\nedge2x 0 012001200 1.5".parse::<PatternWeights>().is_err());
+    assert!("ruleset standard\nedge2x 0 0120012003 1.5".parse::<PatternWeights>().is_err());
+    assert!("ruleset standard\nedge9x 0 0120012000 1.5".parse::<PatternWeights>().is_err());
+}
+
+#[test]
+fn weights_say_which_ruleset_they_are_for() {
+    let anti = PatternWeights::new(Ruleset::AntiOthello);
+    assert_eq!(anti.to_string().parse::<PatternWeights>().unwrap().ruleset(), Ruleset::AntiOthello);
+
+    assert!("bias 0 - 2.5".parse::<PatternWeights>().is_err());
+    assert!("bias 0 - 2.5\nruleset standard".parse::<PatternWeights>().is_err());
+    assert!("ruleset reversi\nbias 0 - 2.5".parse::<PatternWeights>().is_err());
+}
+
+#[test]
+fn weights_for_another_ruleset_are_not_loaded_or_used() {
+    let data_dir = fresh_data_dir();
+    let path = data_dir.join("pattern_weights.txt");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::write(&path, "ruleset anti-othello\nbias 0 - 2.5\n").unwrap();
+    let standard = PatternWeights::load(&path, Ruleset::Standard);
+    let anti = PatternWeights::load(&path, Ruleset::AntiOthello);
+    fs::remove_dir_all(&data_dir).unwrap();
+
+    assert!(standard.is_err());
+    let anti = anti.unwrap();
+    let board = OthelloBoard::new();
+    let heuristic = HeuristicEvaluator::new(Ruleset::Standard);
+
+    let evaluator = engine::strongest_evaluator(Ruleset::Standard, Some(&anti));
+    assert_eq!(evaluator.evaluate(&board, Player::Black), heuristic.evaluate(&board, Player::Black));
+    let evaluator = engine::strongest_evaluator(Ruleset::AntiOthello, Some(&anti));
+    assert_eq!(evaluator.evaluate(&board, Player::Black), 250);
+}
+
+#[test]
+fn predictions_are_from_the_player_s_point_of_view() {
+    let weights: PatternWeights = "ruleset standard\nbias 0 - 2.5".parse().unwrap();
+    let evaluator = PatternEvaluator::new(weights.clone());
+    let board = OthelloBoard::new();
+
+    assert_eq!(weights.predict(&board, Player::Black), Some(2.5));
+    assert_eq!(evaluator.evaluate(&board, Player::Black), 250);
+}
+
+#[test]
+fn symmetric_patterns_score_each_set_of_squares_once() {
+    // Every edge and short diagonal is empty at the start. There are four of each, and
+    // each reads the same squares backwards under another symmetry.
+    let weights: PatternWeights = "ruleset standard\nedge2x 0 0000000000 1\ndiagonal4 0 0000 1".parse().unwrap();
+
+    assert_eq!(weights.predict(&OthelloBoard::new(), Player::Black), Some(8.0));
+}
+
+#[test]
+fn bit_boards_are_predicted_like_othello_boards() {
+    let (_, weights) = trained_weights(3, 3);
+    let evaluator = PatternEvaluator::new(weights.clone());
+    let mut board = random_game(10);
+
+    while board.undo().is_some() {
+        let bit_board = BitBoard::try_from(&board).unwrap();
+        for player in [Player::Black, Player::White] {
+            assert_eq!(Some(weights.predict_bit_board(&bit_board, player)), weights.predict(&board, player));
+            assert_eq!(evaluator.evaluate_bit_board(&bit_board, player), evaluator.evaluate(&board, player));
+        }
+    }
+}
+
+#[test]
+fn boards_without_patterns_fall_back_to_the_heuristic() {
+    let (_, weights) = trained_weights(3, 3);
+    let evaluator = PatternEvaluator::new(weights.clone());
+    let heuristic = HeuristicEvaluator::new(Ruleset::Standard);
+
+    let small = OthelloBoard::with_size(6).unwrap();
+    assert_eq!(weights.predict(&small, Player::Black), None);
+    assert_eq!(evaluator.evaluate(&small, Player::Black), heuristic.evaluate(&small, Player::Black));
+}
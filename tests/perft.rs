@@ -0,0 +1,73 @@
+use othello_rs::game_logic::{Board, OthelloBoard, Player, Symmetry};
+
+mod common;
+
+use common::square;
+
+/// Published node counts for the standard 8x8 starting position, where a forced pass
+/// counts as a move.
+const STARTING_POSITION_COUNTS: [u64; 8] = [4, 12, 56, 244, 1396, 8200, 55092, 390216];
+
+#[test]
+fn perft_matches_reference_counts() {
+    let board = OthelloBoard::new();
+
+    for (depth, expected) in (1..).zip(STARTING_POSITION_COUNTS) {
+        assert_eq!(board.perft(depth), expected, "perft({depth}) of the starting position");
+    }
+}
+
+#[test]
+#[ignore = "slow without optimisations"]
+fn perft_matches_reference_counts_past_first_game_end() {
+    // The shortest possible games end after nine moves, so these depths also cover
+    // paths cut short by a finished game.
+    let board = OthelloBoard::new();
+
+    assert_eq!(board.perft(9), 3005288);
+    assert_eq!(board.perft(10), 24571284);
+}
+
+#[test]
+fn perft_of_zero_depth_is_the_position_itself() {
+    assert_eq!(OthelloBoard::new().perft(0), 1);
+}
+
+#[test]
+fn perft_leaves_the_board_untouched() {
+    let board = OthelloBoard::new();
+    board.perft(4);
+
+    assert_eq!(board.to_string(), OthelloBoard::new().to_string());
+    assert!(board.history().is_empty());
+}
+
+#[test]
+fn perft_is_the_same_for_symmetric_positions() {
+    let mut board = OthelloBoard::new();
+    board.set_piece(square("d3"), Player::Black).unwrap();
+    board.set_piece(square("c3"), Player::White).unwrap();
+    let expected = board.perft(5);
+
+    for symmetry in Symmetry::ALL {
+        assert_eq!(board.transformed(symmetry).perft(5), expected, "{symmetry:?}");
+    }
+}
+
+#[test]
+fn forced_pass_counts_as_a_move() {
+    let board: OthelloBoard = "OOO- XOX- XXOX XOOO X".parse().unwrap();
+
+    assert!(board.must_pass(Player::Black));
+    assert_eq!(board.perft(1), 1);
+    assert_eq!(board.perft(2), board.legal_moves(Player::White).len() as u64);
+}
+
+#[test]
+fn finished_game_counts_as_a_single_leaf() {
+    let board: OthelloBoard = "XXXX XXXX XXXX XXX- O".parse().unwrap();
+
+    assert!(board.is_game_over());
+    assert_eq!(board.perft(1), 1);
+    assert_eq!(board.perft(5), 1);
+}
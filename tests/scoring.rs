@@ -0,0 +1,52 @@
+use othello_rs::game_logic::{Board, OthelloBoard, Player, Ruleset};
+
+#[test]
+fn final_score_awards_empty_squares_to_the_winner() {
+    // White was wiped out with four squares still empty.
+    let board: OthelloBoard = "XX-- XXXX XXXX X--X O".parse().unwrap();
+
+    assert!(board.is_game_over());
+    assert_eq!(board.count_pieces(), (12, 0));
+    assert_eq!(board.final_score(), (16, 0));
+}
+
+#[test]
+fn final_score_splits_empty_squares_on_a_tie() {
+    let board: OthelloBoard = "OO-- XXXX OOOO XX-- X".parse().unwrap();
+
+    assert_eq!(board.final_score(), (8, 8));
+}
+
+#[test]
+fn final_score_leaves_out_blocked_squares() {
+    let board: OthelloBoard = "XX-# XXXX XXXX X#-X O".parse().unwrap();
+
+    assert_eq!(board.final_score(), (14, 0));
+}
+
+#[test]
+fn final_score_keeps_a_tie_with_an_odd_number_of_empty_squares_drawn() {
+    // A tie at six pieces each with three empty squares, one of the four being blocked.
+    let board: OthelloBoard = "XX-# XXXX OOOO OO-- X".parse().unwrap();
+
+    assert_eq!(board.count_pieces(), (6, 6));
+    assert_eq!(board.count_empty_squares(), 3);
+    assert_eq!(board.final_score(), (7, 7));
+    assert_eq!(Ruleset::Standard.winner(Ruleset::Standard.final_score(&board)), None);
+}
+
+#[test]
+fn anti_othello_is_won_by_the_fewest_pieces() {
+    assert_eq!(Ruleset::Standard.winner((40, 24)), Some(Player::Black));
+    assert_eq!(Ruleset::Standard.winner((10, 54)), Some(Player::White));
+    assert_eq!(Ruleset::AntiOthello.winner((40, 24)), Some(Player::White));
+    assert_eq!(Ruleset::AntiOthello.winner((10, 54)), Some(Player::Black));
+}
+
+#[test]
+fn ties_have_no_winner_under_either_ruleset() {
+    for ruleset in Ruleset::ALL {
+        assert_eq!(ruleset.winner((32, 32)), None, "{ruleset:?}");
+        assert_eq!(ruleset.winner((0, 0)), None, "{ruleset:?}");
+    }
+}
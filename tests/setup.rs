@@ -0,0 +1,72 @@
+use othello_rs::game_logic::{Board, BoardLayout, Handicap, OthelloBoard, Player};
+
+mod common;
+
+use common::square;
+
+#[test]
+fn only_empty_boards_start_with_the_free_opening() {
+    let mut board = OthelloBoard::empty(8).unwrap();
+    assert!(board.in_opening_phase());
+    assert_eq!(board.legal_moves(Player::Black).len(), 4);
+
+    for name in ["d4", "e4", "d5", "e5"] {
+        board.set_piece(square(name), board.side_to_move()).unwrap();
+    }
+    assert!(!board.in_opening_phase());
+
+    board.undo();
+    assert!(board.in_opening_phase());
+}
+
+#[test]
+fn boards_with_an_empty_centre_are_not_in_the_free_opening() {
+    // Nothing but a few pieces in the top left, with the centre left empty.
+    let board: OthelloBoard = format!("X-O{} X", "-".repeat(61)).parse().unwrap();
+
+    assert!(!board.in_opening_phase());
+    assert!(board.legal_moves(Player::Black).is_empty());
+    assert!(board.is_game_over());
+}
+
+#[test]
+fn random_obstacles_leave_black_every_opening_move() {
+    for size in [4, 6, 8, 10] {
+        for seed in 0..200 {
+            let mut board = OthelloBoard::with_size(size).unwrap();
+            board.block_squares(&BoardLayout::RandomObstacles.blocked_squares(size, seed)).unwrap();
+
+            assert_eq!(board.legal_moves(Player::Black).len(), 4, "{size}x{size} board, seed {seed}");
+            assert!(!board.must_pass(Player::Black));
+        }
+    }
+}
+
+#[test]
+fn handicaps_give_between_one_and_four_corners() {
+    assert!(Handicap::new(Player::White, 0).is_err());
+    assert!(Handicap::new(Player::White, Handicap::MAX_CORNERS + 1).is_err());
+    assert_eq!(Handicap::new(Player::White, Handicap::MAX_CORNERS).unwrap().corners(), 4);
+}
+
+#[test]
+fn handicap_corners_must_be_empty_and_unblocked() {
+    let handicap = Handicap::new(Player::Black, 2).unwrap();
+
+    let mut blocked = OthelloBoard::new();
+    blocked.block_squares(&[square("h8")]).unwrap();
+    assert!(blocked.place_handicap(handicap).is_err());
+    assert_eq!(blocked.get_piece_at(square("a1")), None);
+
+    let mut occupied: OthelloBoard = "O--- -XO- -OX- ---- X".parse().unwrap();
+    assert!(occupied.place_handicap(handicap).is_err());
+    assert_eq!(occupied.get_piece_at(square("d4")), None);
+}
+
+#[test]
+fn handicaps_are_only_placed_before_the_first_move() {
+    let mut board = OthelloBoard::new();
+    board.set_piece(square("d3"), Player::Black).unwrap();
+
+    assert!(board.place_handicap(Handicap::new(Player::White, 1).unwrap()).is_err());
+}
@@ -0,0 +1,76 @@
+use othello_rs::game_logic::{BoardLayout, OthelloBoard, Rng, Symmetry};
+
+mod common;
+
+use common::random_position_on;
+
+fn obstacle_board(seed: u64, size: usize) -> OthelloBoard {
+    let mut board = OthelloBoard::with_size(size).unwrap();
+    board.block_squares(&BoardLayout::RandomObstacles.blocked_squares(size, seed)).unwrap();
+    board
+}
+
+#[test]
+fn inverse_undoes_every_symmetry() {
+    for size in [4, 6, 8, 10, 16] {
+        let board = OthelloBoard::with_size(size).unwrap();
+        for symmetry in Symmetry::ALL {
+            for square in board.squares() {
+                let moved = symmetry.apply(square, size);
+                assert!(board.contains(moved), "{symmetry:?} moves {square} off the board");
+                assert_eq!(symmetry.inverse().apply(moved, size), square, "{symmetry:?} on {square}");
+            }
+        }
+    }
+}
+
+#[test]
+fn symmetries_are_all_different() {
+    let square = "b1".parse().unwrap();
+    let mut images: Vec<_> = Symmetry::ALL.iter().map(|symmetry| symmetry.apply(square, 8)).collect();
+    images.sort();
+    images.dedup();
+
+    assert_eq!(images.len(), 8);
+}
+
+#[test]
+fn transformed_boards_move_every_square() {
+    let board = random_position_on(obstacle_board(3, 8), &mut Rng::new(3), 20);
+    for symmetry in Symmetry::ALL {
+        let transformed = board.transformed(symmetry);
+        for square in board.squares() {
+            let moved = symmetry.apply(square, 8);
+            assert_eq!(transformed.get_piece_at(moved), board.get_piece_at(square));
+            assert_eq!(transformed.is_blocked(moved), board.is_blocked(square));
+        }
+        assert_eq!(transformed.side_to_move(), board.side_to_move());
+    }
+}
+
+#[test]
+fn every_transform_has_the_same_canonical_form() {
+    for (seed, size) in (0..30).zip([6, 8, 10].into_iter().cycle()) {
+        let board = random_position_on(obstacle_board(seed, size), &mut Rng::new(seed), seed as usize);
+        let (canonical, _) = board.canonical();
+
+        for symmetry in Symmetry::ALL {
+            let (other, _) = board.transformed(symmetry).canonical();
+            assert_eq!(other.to_string(), canonical.to_string(), "{symmetry:?} of {board}");
+        }
+    }
+}
+
+#[test]
+fn canonical_symmetry_maps_back_onto_the_board() {
+    for seed in 0..20 {
+        let board = random_position_on(obstacle_board(seed, 8), &mut Rng::new(seed), 10 + seed as usize);
+        let (canonical, symmetry) = board.canonical();
+
+        for square in canonical.squares() {
+            let original = symmetry.apply(square, 8);
+            assert_eq!(board.get_piece_at(original), canonical.get_piece_at(square), "{board}");
+            assert_eq!(board.is_blocked(original), canonical.is_blocked(square), "{board}");
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use othello_rs::game_logic::{Board, Handicap, OthelloBoard, Player, Rng};
+
+mod common;
+
+use common::play_random_move;
+
+/// The hash of the same position built from scratch out of its notation.
+fn hash_from_scratch(board: &OthelloBoard) -> u64 {
+    board.to_string().parse::<OthelloBoard>().unwrap().zobrist_hash()
+}
+
+/// Plays random moves, passes, undos and redos, checking after each of them that the
+/// incrementally updated hash agrees with one computed from scratch.
+fn check_random_walk(mut board: OthelloBoard, seed: u64) {
+    let mut rng = Rng::new(seed);
+    assert_eq!(board.zobrist_hash(), hash_from_scratch(&board), "{board}");
+
+    for _ in 0..400 {
+        match rng.below(10) {
+            0..=1 => {
+                board.undo();
+            },
+            2 => {
+                board.redo();
+            },
+            _ if board.is_game_over() => {
+                board.undo();
+            },
+            _ => {
+                play_random_move(&mut board, &mut rng);
+            }
+        }
+        assert_eq!(board.zobrist_hash(), hash_from_scratch(&board), "{board}");
+    }
+}
+
+#[test]
+fn incremental_hash_matches_a_hash_from_scratch() {
+    for seed in 0..20 {
+        check_random_walk(OthelloBoard::new(), seed);
+    }
+}
+
+#[test]
+fn incremental_hash_matches_on_other_setups() {
+    let mut blocked = OthelloBoard::with_size(10).unwrap();
+    blocked.block_squares(&["a1".parse().unwrap(), "j10".parse().unwrap()]).unwrap();
+    check_random_walk(blocked, 1);
+
+    let mut handicap = OthelloBoard::new();
+    handicap.place_handicap(Handicap::new(Player::Black, 2).unwrap()).unwrap();
+    check_random_walk(handicap, 2);
+
+    check_random_walk(OthelloBoard::empty(6).unwrap(), 3);
+}
+
+#[test]
+fn hash_tells_the_side_to_move_apart() {
+    let black: OthelloBoard = OthelloBoard::new();
+    let white: OthelloBoard = black.to_string().replace(" X", " O").parse().unwrap();
+
+    assert_ne!(black.zobrist_hash(), white.zobrist_hash());
+}